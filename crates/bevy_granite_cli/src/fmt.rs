@@ -0,0 +1,107 @@
+use crate::scene;
+use bevy_granite_core::entities::format_scene_data;
+use std::{collections::HashSet, path::Path};
+
+/// Rewrite a scene in the same canonical form the editor saves
+/// With check nothing is written, scenes that would change count as failures
+pub fn run(path: &Path, check: bool) -> bool {
+    let contents = match scene::read(path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            eprintln!("{}", error);
+            return false;
+        }
+    };
+
+    let formatted = match format(path, &contents) {
+        Ok(formatted) => formatted,
+        Err(error) => {
+            eprintln!("{}", error);
+            return false;
+        }
+    };
+
+    if formatted == contents {
+        println!("{}: unchanged", path.display());
+        return true;
+    }
+
+    if check {
+        eprintln!("{}: would reformat", path.display());
+        return false;
+    }
+
+    match scene::write(path, &formatted, false) {
+        Ok(()) => {
+            println!("{}: formatted", path.display());
+            true
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            false
+        }
+    }
+}
+
+/// Scene contents in canonical form, or why they can not be formatted
+pub fn format(path: &Path, contents: &str) -> Result<String, String> {
+    // No migrations here, older scenes have to go through upgrade first
+    let scene_data = scene::parse(path, contents)
+        .map_err(|error| format!("{} (older scenes need granite-cli upgrade)", error))?;
+
+    // Parents are matched by UUID, so with duplicates the canonical order is not well defined
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = scene_data
+        .entities
+        .iter()
+        .find(|entity| !seen.insert(entity.identity.uuid))
+    {
+        return Err(format!(
+            "{}: error: duplicate UUID {} (granite-cli validate lists them)",
+            path.display(),
+            duplicate.identity.uuid
+        ));
+    }
+
+    format_scene_data(scene_data)
+        .map_err(|error| format!("{}: error: failed to format: {}", path.display(), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_granite_core::get_current_scene_version;
+
+    // Child before its parent, squashed on one line and with a stale entity_count
+    fn unformatted() -> String {
+        format!(
+            r#"(metadata: (format_version: "{}", entity_count: 5), entities: [
+(identity: (uuid: "0d9c1b5e-7a3f-4e21-8c44-2b6f9e1a7d30", name: "Child", class: Empty(())), transform: (position: (1.0, 0.0, 0.0), rotation: (0.0, 0.0, 0.0, 1.0), scale: (1.0, 1.0, 1.0)), parent: Some("6c0f6a8e-2f44-4d2a-9d6b-0c6a1f2b3c4d"), components: Some({{"game::Health": (value: 3)}})),
+(identity: (uuid: "6c0f6a8e-2f44-4d2a-9d6b-0c6a1f2b3c4d", name: "Parent", class: Empty(())), transform: (position: (0.0, 0.0, 0.0), rotation: (0.0, 0.0, 0.0, 1.0), scale: (1.0, 1.0, 1.0)))])"#,
+            get_current_scene_version()
+        )
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let path = Path::new("test.scene");
+        let formatted = format(path, &unformatted()).unwrap();
+        assert_ne!(formatted, unformatted());
+        assert_eq!(format(path, &formatted).unwrap(), formatted);
+
+        let scene_data = scene::parse(path, &formatted).unwrap();
+        assert_eq!(scene_data.metadata.entity_count, 2);
+        assert_eq!(scene_data.entities[0].identity.name, "Parent");
+        assert!(formatted.contains("\"game::Health\""));
+    }
+
+    #[test]
+    fn duplicate_uuids_are_refused() {
+        let duplicated = unformatted().replace(
+            "0d9c1b5e-7a3f-4e21-8c44-2b6f9e1a7d30",
+            "6c0f6a8e-2f44-4d2a-9d6b-0c6a1f2b3c4d",
+        );
+        let error = format(Path::new("test.scene"), &duplicated).unwrap_err();
+        assert!(error.contains("duplicate UUID"), "{}", error);
+    }
+}
@@ -0,0 +1,80 @@
+use std::process::ExitCode;
+
+mod fmt;
+mod scene;
+mod stats;
+mod upgrade;
+mod validate;
+
+// granite-cli
+// Headless tool for .scene files, meant to run in CI before anyone opens the editor.
+// Only the scene data types and version utilities from core are used, no App is ever built,
+// so it needs no window or GPU. Exits with 1 when any scene fails.
+
+const USAGE: &str = "Usage: granite-cli <command> [--verbose] <scene or folder>...
+
+Commands:
+  validate          Parse, check version, dangling parents, duplicate UUIDs and entity_count
+  fmt [--check]     Rewrite scenes in canonical pretty form, --check only reports them
+  upgrade [--check] Apply scene format migrations and write the result back, --check only reports them
+  stats             Print entity, class and component counts
+
+Folders are searched recursively for .scene files, compressed .scene.zst and .scene.gz included";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(command) = args.next() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let mut check = false;
+    let mut inputs = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            // Core logging is silent until set up, it is only useful when debugging a scene
+            "--verbose" | "-v" => bevy_granite_logging::setup_logging(),
+            _ => inputs.push(arg),
+        }
+    }
+
+    let run: fn(&std::path::Path, bool) -> bool = match command.as_str() {
+        "validate" | "stats" if check => {
+            eprintln!("'{}' does not take --check\n\n{}", command, USAGE);
+            return ExitCode::FAILURE;
+        }
+        "validate" => |path, _| validate::run(path),
+        "fmt" => fmt::run,
+        "upgrade" => upgrade::run,
+        "stats" => |path, _| stats::run(path),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        _ => {
+            eprintln!("Unknown command '{}'\n\n{}", command, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let paths = match scene::collect_scene_paths(&inputs) {
+        Ok(paths) if !paths.is_empty() => paths,
+        Ok(_) => {
+            eprintln!("No .scene files given\n\n{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Every scene is processed even after a failure, so CI reports all broken scenes at once
+    let failed = paths.iter().filter(|path| !run(path, check)).count();
+    if failed > 0 {
+        eprintln!("{} of {} scenes failed", failed, paths.len());
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
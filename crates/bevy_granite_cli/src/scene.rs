@@ -0,0 +1,73 @@
+use bevy_granite_core::{
+    entities::{parse_scene_data, SceneData},
+    is_scene_file, read_scene_file, rewrite_scene_file, write_scene_file, GraniteSceneError,
+    SceneCompression,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Expand the given files and folders into a sorted list of scene files, compressed ones included
+pub fn collect_scene_paths(inputs: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut paths = Vec::new();
+    for input in inputs {
+        let path = PathBuf::from(input);
+        if path.is_dir() {
+            collect_from_dir(&path, &mut paths)
+                .map_err(|e| format!("Failed to read folder '{}': {}", path.display(), e))?;
+        } else if path.exists() {
+            paths.push(path);
+        } else {
+            return Err(format!("No such file or folder: '{}'", path.display()));
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+fn collect_from_dir(dir: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_from_dir(&path, paths)?;
+        } else if is_scene_file(&path) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Read a scene as RON, decompressing it if needed
+pub fn read(path: &Path) -> Result<String, GraniteSceneError> {
+    read_scene_file(path).map_err(|source| GraniteSceneError::Io {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+/// Write a scene back with the same compression it was read with
+/// Written to a temporary file and renamed, so an interrupted run never leaves half a scene.
+/// With backup the original is kept as a .bak, like the editor does when it upgrades in place
+pub fn write(path: &Path, contents: &str, backup: bool) -> Result<(), GraniteSceneError> {
+    let compression = fs::read(path)
+        .map(|existing| SceneCompression::detect(&existing))
+        .unwrap_or_else(|_| SceneCompression::from_path(path));
+    compression
+        .compress(contents)
+        .and_then(|bytes| match backup {
+            true => rewrite_scene_file(path, &bytes),
+            false => write_scene_file(path, &bytes, 0),
+        })
+        .map_err(|source| GraniteSceneError::Io {
+            path: path.display().to_string(),
+            source,
+        })
+}
+
+/// Parse scene RON as it is, without running migrations
+/// Unknown classes are kept as raw RON, so fmt and upgrade write them back untouched
+pub fn parse(path: &Path, contents: &str) -> Result<SceneData, GraniteSceneError> {
+    parse_scene_data(&path.display().to_string(), contents)
+}
@@ -0,0 +1,108 @@
+use crate::scene;
+use bevy_granite_core::{GraniteType, SceneMigrations};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+use uuid::Uuid;
+
+/// Print a short summary of what a scene contains
+/// Older scenes are migrated in memory first, the file is not touched
+pub fn run(path: &Path) -> bool {
+    let migrated =
+        match scene::read(path).map(|contents| SceneMigrations::default().migrate(&contents)) {
+            Ok(Ok(migrated)) => migrated,
+            Ok(Err(message)) => {
+                eprintln!("{}: error: {}", path.display(), message);
+                return false;
+            }
+            Err(error) => {
+                eprintln!("{}", error);
+                return false;
+            }
+        };
+    let scene_data = match scene::parse(path, &migrated.contents) {
+        Ok(scene_data) => scene_data,
+        Err(error) => {
+            eprintln!("{}", error);
+            return false;
+        }
+    };
+
+    // Dangling parents are left out, those entities are spawned as roots
+    let uuids: HashSet<Uuid> = scene_data
+        .entities
+        .iter()
+        .map(|entity| entity.identity.uuid)
+        .collect();
+    let parents: HashMap<Uuid, Option<Uuid>> = scene_data
+        .entities
+        .iter()
+        .map(|entity| {
+            let parent = entity.parent.filter(|parent| uuids.contains(parent));
+            (entity.identity.uuid, parent)
+        })
+        .collect();
+
+    let mut classes: BTreeMap<String, usize> = BTreeMap::new();
+    let mut components: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut roots = 0;
+    let mut max_depth = 0;
+    for entity in scene_data.entities.iter() {
+        let class = match entity.preserved_class_name() {
+            Some(class) => format!("{} (unknown)", class),
+            None => entity.identity.class.type_name(),
+        };
+        *classes.entry(class).or_default() += 1;
+        for type_path in entity.components.iter().flat_map(|c| c.keys()) {
+            *components.entry(type_path).or_default() += 1;
+        }
+
+        // Walk up to the root, capped so parent cycles can not hang
+        let mut depth = 0;
+        let mut parent = parents.get(&entity.identity.uuid).copied().flatten();
+        while let Some(uuid) = parent.filter(|_| depth < parents.len()) {
+            depth += 1;
+            parent = parents.get(&uuid).copied().flatten();
+        }
+        if depth == 0 {
+            roots += 1;
+        }
+        max_depth = max_depth.max(depth);
+    }
+
+    println!("{}", path.display());
+    println!("  format version  {}", migrated.original_version);
+    let metadata = &scene_data.metadata;
+    if let Some(description) = &metadata.description {
+        println!("  description     {}", description);
+    }
+    if let Some(author) = &metadata.author {
+        println!("  author          {}", author);
+    }
+    if !metadata.tags.is_empty() {
+        println!("  tags            {}", metadata.tags.join(", "));
+    }
+    if let Some(modified) = &metadata.modified {
+        println!("  modified        {}", modified);
+    }
+    if !metadata.custom.is_empty() {
+        let keys: Vec<&str> = metadata.custom.keys().map(String::as_str).collect();
+        println!("  custom          {}", keys.join(", "));
+    }
+    println!(
+        "  entities        {} ({} roots, max depth {})",
+        scene_data.entities.len(),
+        roots,
+        max_depth
+    );
+    println!("  classes");
+    for (class, count) in classes.iter() {
+        println!("    {:<40} {}", class, count);
+    }
+    println!("  components");
+    for (type_path, count) in components.iter() {
+        println!("    {:<40} {}", type_path, count);
+    }
+    true
+}
@@ -0,0 +1,71 @@
+use crate::scene;
+use bevy_granite_core::{entities::format_scene_data, SceneMigrations};
+use std::path::Path;
+
+/// Run a scene through the built-in format migrations and write the upgraded, formatted result
+/// Migrations a game registers on its own SceneMigrations are not known here
+/// With check nothing is written, scenes that need an upgrade count as failures
+pub fn run(path: &Path, check: bool) -> bool {
+    let contents = match scene::read(path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            eprintln!("{}", error);
+            return false;
+        }
+    };
+
+    let migrated = match SceneMigrations::default().migrate(&contents) {
+        Ok(migrated) => migrated,
+        Err(message) => {
+            eprintln!("{}: error: {}", path.display(), message);
+            return false;
+        }
+    };
+
+    if !migrated.was_migrated() {
+        println!(
+            "{}: already at format version '{}'",
+            path.display(),
+            migrated.original_version
+        );
+        return true;
+    }
+
+    if check {
+        eprintln!(
+            "{}: would upgrade from '{}' ({})",
+            path.display(),
+            migrated.original_version,
+            migrated.applied.join(", ")
+        );
+        return false;
+    }
+
+    let upgraded = match scene::parse(path, &migrated.contents).map(format_scene_data) {
+        Ok(Ok(upgraded)) => upgraded,
+        Ok(Err(error)) => {
+            eprintln!("{}: error: failed to format: {}", path.display(), error);
+            return false;
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            return false;
+        }
+    };
+
+    match scene::write(path, &upgraded, true) {
+        Ok(()) => {
+            println!(
+                "{}: upgraded from '{}' ({})",
+                path.display(),
+                migrated.original_version,
+                migrated.applied.join(", ")
+            );
+            true
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            false
+        }
+    }
+}
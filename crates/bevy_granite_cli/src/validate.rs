@@ -0,0 +1,315 @@
+use crate::scene;
+use bevy_granite_core::{
+    get_current_scene_version, is_scene_version_compatible, shared::read_scene_format_version,
+    SceneMigrations,
+};
+use std::{collections::HashMap, path::Path};
+use uuid::Uuid;
+
+/// What validating a scene found, messages do not include the path
+#[derive(Debug, Default)]
+pub struct Report {
+    pub entities: usize,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Check a scene the way the editor would load it, plus the problems loading silently tolerates
+/// Older scenes are migrated in memory first, a pending upgrade is only a warning
+pub fn run(path: &Path) -> bool {
+    let report = match scene::read(path) {
+        Ok(contents) => check(path, &contents),
+        Err(error) => {
+            eprintln!("{}", error);
+            return false;
+        }
+    };
+
+    for warning in report.warnings.iter() {
+        println!("{}: warning: {}", path.display(), warning);
+    }
+
+    if report.errors.is_empty() {
+        println!("{}: ok ({} entities)", path.display(), report.entities);
+        return true;
+    }
+
+    for error in report.errors.iter() {
+        eprintln!("{}: error: {}", path.display(), error);
+    }
+    false
+}
+
+/// Validate scene contents, `path` is only used in parse errors
+pub fn check(path: &Path, contents: &str) -> Report {
+    let mut report = Report::default();
+
+    let version = match read_scene_format_version(contents) {
+        Ok(version) => version,
+        Err(error) => {
+            report.errors.push(format!(
+                "missing or unreadable metadata.format_version: {}",
+                error
+            ));
+            return report;
+        }
+    };
+
+    let migrated = match SceneMigrations::default().migrate(contents) {
+        Ok(migrated) => migrated,
+        Err(message) => {
+            report.errors.push(format!(
+                "unsupported format version '{}': {}",
+                version, message
+            ));
+            return report;
+        }
+    };
+
+    let current = get_current_scene_version();
+    if migrated.was_migrated() {
+        report.warnings.push(format!(
+            "format version '{}' needs upgrading to '{}' (granite-cli upgrade)",
+            version, current
+        ));
+    } else if version != current {
+        if !is_scene_version_compatible(&version) {
+            report.errors.push(format!(
+                "format version '{}' is not supported (current: '{}')",
+                version, current
+            ));
+            return report;
+        }
+        report.warnings.push(format!(
+            "format version '{}' differs from current '{}'",
+            version, current
+        ));
+    }
+
+    let scene_data = match scene::parse(path, &migrated.contents) {
+        Ok(scene_data) => scene_data,
+        Err(error) => {
+            report.errors.push(error.to_string());
+            return report;
+        }
+    };
+    report.entities = scene_data.entities.len();
+
+    // Classes from other tools are kept as they are when saving, so they only warrant a warning
+    for entity in scene_data.entities.iter() {
+        if let Some(class) = entity.preserved_class_name() {
+            report.warnings.push(format!(
+                "'{}' ({}) has unknown class '{}', it is kept as is",
+                entity.identity.name, entity.identity.uuid, class
+            ));
+        }
+    }
+
+    let problems = &mut report.errors;
+
+    if scene_data.metadata.entity_count != scene_data.entities.len() {
+        problems.push(format!(
+            "entity_count is {} but the scene has {} entities (granite-cli fmt fixes it)",
+            scene_data.metadata.entity_count,
+            scene_data.entities.len()
+        ));
+    }
+
+    let mut by_uuid: HashMap<Uuid, Vec<&str>> = HashMap::new();
+    for entity in scene_data.entities.iter() {
+        by_uuid
+            .entry(entity.identity.uuid)
+            .or_default()
+            .push(&entity.identity.name);
+    }
+
+    let mut duplicates: Vec<_> = by_uuid
+        .iter()
+        .filter(|(_, names)| names.len() > 1)
+        .collect();
+    duplicates.sort();
+    for (uuid, names) in duplicates {
+        problems.push(format!(
+            "duplicate UUID {} on {} entities: {}",
+            uuid,
+            names.len(),
+            names.join(", ")
+        ));
+    }
+
+    for entity in scene_data.entities.iter() {
+        if let Some(parent) = entity.parent {
+            if parent == entity.identity.uuid {
+                problems.push(format!(
+                    "'{}' ({}) is its own parent",
+                    entity.identity.name, entity.identity.uuid
+                ));
+            } else if !by_uuid.contains_key(&parent) {
+                problems.push(format!(
+                    "'{}' ({}) has dangling parent {}",
+                    entity.identity.name, entity.identity.uuid, parent
+                ));
+            }
+        }
+    }
+
+    // Self parents are reported above, longer cycles once each
+    let parents: HashMap<Uuid, Uuid> = scene_data
+        .entities
+        .iter()
+        .filter_map(|entity| Some((entity.identity.uuid, entity.parent?)))
+        .collect();
+    let mut cycles: Vec<Vec<Uuid>> = Vec::new();
+    for entity in scene_data.entities.iter() {
+        let start = entity.identity.uuid;
+        let mut chain = vec![start];
+        while let Some(parent) = parents.get(chain.last().unwrap()).copied() {
+            if parent == start {
+                let mut cycle = chain.clone();
+                cycle.sort();
+                if chain.len() > 1 && !cycles.contains(&cycle) {
+                    cycles.push(cycle);
+                }
+                break;
+            }
+            if chain.contains(&parent) {
+                break;
+            }
+            chain.push(parent);
+        }
+    }
+    cycles.sort();
+    for cycle in cycles {
+        let members: Vec<String> = cycle
+            .iter()
+            .map(|uuid| format!("'{}' ({})", by_uuid[uuid][0], uuid))
+            .collect();
+        problems.push(format!("parent cycle between {}", members.join(", ")));
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARENT: &str = "6c0f6a8e-2f44-4d2a-9d6b-0c6a1f2b3c4d";
+    const CHILD: &str = "0d9c1b5e-7a3f-4e21-8c44-2b6f9e1a7d30";
+    const MISSING: &str = "9a8b7c6d-5e4f-4a3b-8c2d-1e0f9a8b7c6d";
+
+    // Scene with (uuid, name, parent) entities, entity_count as given
+    fn scene(
+        version: &str,
+        entity_count: usize,
+        entities: &[(&str, &str, Option<&str>)],
+    ) -> String {
+        let entities: String = entities
+            .iter()
+            .map(|(uuid, name, parent)| {
+                let parent = parent.map_or("None".to_string(), |p| format!("Some(\"{p}\")"));
+                format!(
+                    r#"
+        (
+            identity: (uuid: "{uuid}", name: "{name}", class: Empty(())),
+            transform: (position: (0.0, 0.0, 0.0), rotation: (0.0, 0.0, 0.0, 1.0), scale: (1.0, 1.0, 1.0)),
+            parent: {parent},
+        ),"#
+                )
+            })
+            .collect();
+        format!(
+            r#"(
+    metadata: (format_version: "{version}", entity_count: {entity_count}),
+    entities: [{entities}
+    ],
+)"#
+        )
+    }
+
+    fn check_scene(contents: &str) -> Report {
+        check(Path::new("test.scene"), contents)
+    }
+
+    #[test]
+    fn valid_scenes_pass() {
+        let report = check_scene(&scene(
+            &get_current_scene_version(),
+            2,
+            &[(PARENT, "Parent", None), (CHILD, "Child", Some(PARENT))],
+        ));
+        assert_eq!(report.entities, 2);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+    }
+
+    #[test]
+    fn dangling_parents_are_errors() {
+        let report = check_scene(&scene(
+            &get_current_scene_version(),
+            1,
+            &[(CHILD, "Child", Some(MISSING))],
+        ));
+        assert_eq!(
+            report.errors,
+            [format!("'Child' ({CHILD}) has dangling parent {MISSING}")]
+        );
+    }
+
+    #[test]
+    fn duplicate_uuids_are_errors() {
+        let report = check_scene(&scene(
+            &get_current_scene_version(),
+            2,
+            &[(PARENT, "A", None), (PARENT, "B", None)],
+        ));
+        assert_eq!(
+            report.errors,
+            [format!("duplicate UUID {PARENT} on 2 entities: A, B")]
+        );
+    }
+
+    #[test]
+    fn wrong_entity_counts_are_errors() {
+        let report = check_scene(&scene(
+            &get_current_scene_version(),
+            3,
+            &[(PARENT, "Parent", None)],
+        ));
+        assert_eq!(
+            report.errors,
+            ["entity_count is 3 but the scene has 1 entities (granite-cli fmt fixes it)"]
+        );
+    }
+
+    #[test]
+    fn parent_cycles_are_errors() {
+        let report = check_scene(&scene(
+            &get_current_scene_version(),
+            2,
+            &[(PARENT, "A", Some(CHILD)), (CHILD, "B", Some(PARENT))],
+        ));
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].starts_with("parent cycle between"));
+    }
+
+    #[test]
+    fn old_format_versions_are_warnings() {
+        let report = check_scene(&scene("0.1.4", 1, &[(PARENT, "Parent", None)]));
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(
+            report.warnings,
+            [format!(
+                "format version '0.1.4' needs upgrading to '{}' (granite-cli upgrade)",
+                get_current_scene_version()
+            )]
+        );
+    }
+
+    #[test]
+    fn unsupported_format_versions_are_errors() {
+        let report = check_scene(&scene("0.1.0", 1, &[(PARENT, "Parent", None)]));
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].starts_with("unsupported format version '0.1.0'"));
+    }
+}
@@ -306,8 +306,6 @@ impl EditableMaterial {
 
     pub fn save_to_file(&mut self) {
         if let Some(def) = &mut self.def {
-            let current_dir = std::env::current_dir().expect("Failed to get current directory");
-
             if !self.path.starts_with("materials/") {
                 log!(
                     LogType::Editor,
@@ -320,7 +318,7 @@ impl EditableMaterial {
                 self.new_material = false;
                 return;
             }
-            let save_path = current_dir.join("assets").join(&self.path);
+            let save_path = rel_asset_to_absolute(&self.path).to_string();
             log!(
                 LogType::Editor,
                 LogLevel::Info,
@@ -2,6 +2,7 @@ use super::{
     AvailableEditableMaterials, EditableMaterial, EditableMaterialError, EditableMaterialField,
     StandardMaterialDef,
 };
+use crate::shared::{absolute_asset_to_rel, rel_asset_to_absolute};
 use bevy::image::{
     ImageAddressMode, ImageFilterMode, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor,
 };
@@ -14,6 +15,7 @@ use bevy_granite_logging::{
     config::{LogCategory, LogLevel, LogType},
     log,
 };
+use std::path::Path;
 
 // This was brutal to figure out and I CANNOT believe the is a .load_with_settings() method...
 /// Helper function to load textures with REPEAT address mode
@@ -48,7 +50,7 @@ pub fn material_from_path_into_scene(
         return Some(existing.clone());
     }
 
-    let ron_path = "assets/".to_string() + path;
+    let ron_path = rel_asset_to_absolute(path).to_string();
     let ron = match std::fs::read_to_string(&ron_path) {
         Ok(content) => content,
         Err(e) => {
@@ -311,7 +313,7 @@ pub fn materials_from_folder_into_scene(
     asset_server: &Res<AssetServer>,
 ) -> Vec<EditableMaterial> {
     let mut created_materials = Vec::new();
-    let assets_folder_path = "assets/".to_string() + folder_path;
+    let assets_folder_path = rel_asset_to_absolute(folder_path).to_string();
 
     // Recursively collect all .mat files
     let mut ron_files = Vec::new();
@@ -399,11 +401,10 @@ fn collect_material_files_recursive(current_dir: &str, ron_files: &mut Vec<Strin
             // Recursively process subdirectory
             collect_material_files_recursive(&path.to_string_lossy(), ron_files);
         } else if path.is_file() && path.extension().is_some_and(|ext| ext == "mat") {
-            // Get the path relative to assets/
-            let path_str = path.to_string_lossy();
-            if let Some(assets_pos) = path_str.find("assets/") {
-                let relative_path = &path_str[assets_pos + 7..]; // Skip "assets/"
-                ron_files.push(relative_path.replace('\\', "/")); // Normalize slashes
+            // Get the path relative to its asset root
+            let relative_path = absolute_asset_to_rel(path.to_string_lossy().to_string());
+            if !Path::new(relative_path.as_ref()).is_absolute() {
+                ron_files.push(relative_path.to_string());
             }
         }
     }
@@ -1,9 +1,20 @@
+use super::{
+    component_hooks::prepare_component_for_save,
+    component_migration::{migrate_component_ron, ComponentMigrator},
+    serialize::{dedent_raw_value, scene_pretty_config},
+    PreservedSceneData,
+};
 use bevy::{
     prelude::*,
-    reflect::{FromType, ReflectDeserialize, TypeRegistration},
+    reflect::{
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        FromType, TypeRegistration,
+    },
 };
 use bevy_granite_logging::{log, LogCategory, LogLevel, LogType};
-use std::{any::Any, borrow::Cow, collections::HashMap};
+use ron::value::RawValue;
+use serde::de::DeserializeSeed;
+use std::{any::Any, borrow::Cow, collections::BTreeMap};
 
 // All structs defined by #[granite_component]
 // get this tag so we can easily filter in UI
@@ -167,18 +178,20 @@ impl ComponentEditor {
     }
 
     /// Save components for entities
+    /// Each component is written as its own nested RON value keyed by type path
+    /// Components with GraniteComponentHooks are written after on_before_save
     pub fn serialize_entity_components(
         &self,
         world: &World,
         entity: Entity,
-    ) -> HashMap<String, String> {
+    ) -> BTreeMap<String, Box<RawValue>> {
         log!(
             LogType::Game,
             LogLevel::Info,
             LogCategory::System,
             "Serialize entity components called"
         );
-        let mut serialized_components = HashMap::new();
+        let mut serialized_components = BTreeMap::new();
         let type_registry = self.type_registry.read();
 
         let entity_ref = world.entity(entity);
@@ -197,12 +210,34 @@ impl ComponentEditor {
 
                     if let Some(reflect_component) = registration.data::<ReflectComponent>() {
                         if let Some(reflected_value) = reflect_component.reflect(entity_ref) {
-                            let serializer = bevy::reflect::serde::ReflectSerializer::new(
-                                reflected_value,
+                            let prepared =
+                                prepare_component_for_save(registration, reflected_value, world);
+                            let serializer = TypedReflectSerializer::new(
+                                prepared
+                                    .as_deref()
+                                    .unwrap_or(reflected_value)
+                                    .as_partial_reflect(),
                                 &type_registry,
                             );
-                            if let Ok(serialized) = ron::to_string(&serializer) {
-                                serialized_components.insert(type_name.to_string(), serialized);
+                            match ron::ser::to_string_pretty(&serializer, scene_pretty_config())
+                                .map_err(|e| e.to_string())
+                                .and_then(|serialized| {
+                                    RawValue::from_boxed_ron(serialized.into_boxed_str())
+                                        .map_err(|e| e.to_string())
+                                }) {
+                                Ok(raw) => {
+                                    serialized_components.insert(type_name.to_string(), raw);
+                                }
+                                Err(e) => {
+                                    log!(
+                                        LogType::Game,
+                                        LogLevel::Error,
+                                        LogCategory::System,
+                                        "Failed to serialize component {}: {}",
+                                        type_name,
+                                        e
+                                    );
+                                }
                             }
                         }
                     }
@@ -210,17 +245,57 @@ impl ComponentEditor {
             }
         }
 
+        // Components this app has no registration for go back as they were loaded
+        if let Some(preserved) = entity_ref.get::<PreservedSceneData>() {
+            for (type_name, ron) in preserved.components.iter() {
+                if serialized_components.contains_key(type_name) {
+                    continue;
+                }
+                match RawValue::from_boxed_ron(ron.clone().into_boxed_str()) {
+                    Ok(raw) => {
+                        serialized_components.insert(type_name.clone(), raw);
+                    }
+                    Err(e) => {
+                        log!(
+                            LogType::Game,
+                            LogLevel::Error,
+                            LogCategory::System,
+                            "Failed to write preserved component {}: {}",
+                            type_name,
+                            e
+                        );
+                    }
+                }
+            }
+        }
+
         serialized_components
     }
 
     /// Insert components from serialized
+    /// `component_versions` are the versions the scene saved them at, older ones are migrated first
     pub fn load_components_from_scene_data(
         &self,
         world: &mut World,
         entity: Entity,
-        serialized_components: HashMap<String, String>,
+        serialized_components: BTreeMap<String, Box<RawValue>>,
+        component_versions: &BTreeMap<String, u32>,
         type_registry: AppTypeRegistry,
     ) {
+        let type_registry = type_registry.read();
+        let mut preserved = PreservedSceneData::default();
+        // Kept as it was loaded, at the version it was saved at, so saving writes it back untouched
+        let mut preserve = |component_name: String, serialized_data: &RawValue| {
+            if let Some(version) = component_versions.get(&component_name) {
+                preserved
+                    .component_versions
+                    .insert(component_name.clone(), *version);
+            }
+            preserved.components.insert(
+                component_name,
+                dedent_raw_value(serialized_data).get_ron().to_string(),
+            );
+        };
         for (component_name, serialized_data) in serialized_components {
             log!(
                 LogType::Game,
@@ -231,73 +306,131 @@ impl ComponentEditor {
                 serialized_data
             );
 
-            if let Some(registration) = type_registry.read().get_with_type_path(&component_name) {
-                // Parse the wrapper to extract just the component data part
-                if let Ok(parsed) = ron::from_str::<HashMap<String, ron::Value>>(&serialized_data) {
-                    if let Some(_component_value) = parsed.get(&component_name) {
-                        // Find the component name in quotes and extract what comes after the colon
-                        let search_pattern = format!("\"{}\":", component_name);
-                        if let Some(start) = serialized_data.find(&search_pattern) {
-                            let after_colon = start + search_pattern.len();
-                            let ron_part = &serialized_data[after_colon..serialized_data.len() - 1]; // Remove trailing }
-                            let clean_ron = ron_part.trim();
-
-                            if let Ok(mut deserializer) = ron::de::Deserializer::from_str(clean_ron)
-                            {
-                                if let Some(reflect_deserialize) =
-                                    registration.data::<ReflectDeserialize>()
-                                {
-                                    match reflect_deserialize.deserialize(&mut deserializer) {
-                                        Ok(component_data) => {
-                                            if let Some(reflect_component) =
-                                                registration.data::<ReflectComponent>()
-                                            {
-                                                let mut entity_mut = world.entity_mut(entity);
-                                                if entity_mut
-                                                    .contains_type_id(reflect_component.type_id())
-                                                {
-                                                    reflect_component
-                                                        .apply(&mut entity_mut, &*component_data);
-                                                } else {
-                                                    reflect_component.insert(
-                                                        &mut entity_mut,
-                                                        &*component_data,
-                                                        &type_registry.read(),
-                                                    );
-                                                }
-                                                log!(
-                                                    LogType::Game,
-                                                    LogLevel::Info,
-                                                    LogCategory::Entity,
-                                                    "Inserted: {}",
-                                                    component_name
-                                                );
-                                            }
-                                        }
-                                        Err(e) => {
-                                            log!(
-                                                LogType::Game,
-                                                LogLevel::Error,
-                                                LogCategory::System,
-                                                "Failed to deserialize component {}: {:?}",
-                                                component_name,
-                                                e
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            } else {
+            // Unknown components are kept as RON, so saving does not drop them
+            let Some((registration, reflect_component)) = type_registry
+                .get_with_type_path(&component_name)
+                .and_then(|registration| {
+                    Some((registration, registration.data::<ReflectComponent>()?))
+                })
+            else {
                 log!(
                     LogType::Game,
-                    LogLevel::Error,
+                    LogLevel::Warning,
                     LogCategory::System,
-                    "No registration found for component: {}",
+                    "No registration found for component: {}, keeping it as is",
                     component_name
                 );
+                preserve(component_name, &serialized_data);
+                continue;
+            };
+
+            let saved_version = component_versions
+                .get(&component_name)
+                .copied()
+                .unwrap_or(1);
+            let migrated = match migrate_component_ron(
+                &serialized_data,
+                saved_version,
+                registration,
+                &type_registry,
+            ) {
+                Ok(migrated) => migrated,
+                Err(e) => {
+                    log!(
+                        LogType::Game,
+                        LogLevel::Error,
+                        LogCategory::System,
+                        "Failed to migrate component {} from version {}: {}, keeping it as is",
+                        component_name,
+                        saved_version,
+                        e
+                    );
+                    preserve(component_name, &serialized_data);
+                    continue;
+                }
+            };
+            if let Some(migrator) = registration.data::<ComponentMigrator>() {
+                if migrated.is_some() {
+                    log!(
+                        LogType::Game,
+                        LogLevel::Info,
+                        LogCategory::System,
+                        "Migrated component {} from version {} to {}",
+                        component_name,
+                        saved_version,
+                        migrator.version
+                    );
+                } else if saved_version > migrator.version {
+                    log!(
+                        LogType::Game,
+                        LogLevel::Warning,
+                        LogCategory::System,
+                        "Component {} was saved at version {}, newer than {}, loading it as is",
+                        component_name,
+                        saved_version,
+                        migrator.version
+                    );
+                }
+            }
+            let component_ron = migrated
+                .as_deref()
+                .unwrap_or_else(|| serialized_data.get_ron());
+
+            let component_data = ron::de::Deserializer::from_str(component_ron)
+                .map_err(|e| e.to_string())
+                .and_then(|mut deserializer| {
+                    TypedReflectDeserializer::new(registration, &type_registry)
+                        .deserialize(&mut deserializer)
+                        .map_err(|e| e.to_string())
+                });
+
+            match component_data {
+                Ok(component_data) => {
+                    let mut entity_mut = world.entity_mut(entity);
+                    if entity_mut.contains_type_id(registration.type_id()) {
+                        reflect_component.apply(&mut entity_mut, &*component_data);
+                    } else {
+                        reflect_component.insert(&mut entity_mut, &*component_data, &type_registry);
+                    }
+                    // Saving only writes bridge and exposed components, e.g. imported Bevy scenes
+                    // bring others along, so those are kept as loaded too
+                    if self.should_skip_component(registration) {
+                        preserve(component_name.clone(), &serialized_data);
+                    }
+                    log!(
+                        LogType::Game,
+                        LogLevel::Info,
+                        LogCategory::Entity,
+                        "Inserted: {}",
+                        component_name
+                    );
+                }
+                Err(e) => {
+                    log!(
+                        LogType::Game,
+                        LogLevel::Error,
+                        LogCategory::System,
+                        "Failed to deserialize component {}: {}, keeping it as is",
+                        component_name,
+                        e
+                    );
+                    preserve(component_name, &serialized_data);
+                }
+            }
+        }
+
+        if !preserved.components.is_empty() {
+            let mut entity_mut = world.entity_mut(entity);
+            match entity_mut.get_mut::<PreservedSceneData>() {
+                Some(mut existing) => {
+                    existing.components.extend(preserved.components);
+                    existing
+                        .component_versions
+                        .extend(preserved.component_versions);
+                }
+                None => {
+                    entity_mut.insert(preserved);
+                }
             }
         }
     }
@@ -0,0 +1,90 @@
+// component_hooks.rs
+// Optional save and load hooks for #[granite_component]s, registered as type data with
+// `#[granite_component(hooks)]`. Saving runs on_before_save on a copy of the component, so stripping
+// transient data never touches the live one. Loading runs on_after_load once every component of
+// the scene is inserted and its entity refs resolved, on a copy that is applied back afterwards
+// because the hook gets the whole World
+
+use bevy::{
+    ecs::{entity::Entity, world::World},
+    prelude::{AppTypeRegistry, ReflectComponent, ReflectFromReflect},
+    reflect::{reflect_trait, Reflect, TypeRegistration},
+};
+
+/// Save and load hooks of a #[granite_component]
+/// Implement it and add `hooks`, e.g. `#[granite_component(hooks)]`
+#[reflect_trait]
+pub trait GraniteComponentHooks {
+    /// Called on the copy that is about to be saved, e.g. to strip transient data
+    fn on_before_save(&mut self, _world: &World) {}
+
+    /// Called after the scene is loaded, e.g. to rebuild caches or request assets
+    /// The world still holds the loaded component until this returns
+    fn on_after_load(&mut self, _entity: Entity, _world: &mut World) {}
+}
+
+/// Copy of a component with on_before_save applied, None when it has no hooks
+pub fn prepare_component_for_save(
+    registration: &TypeRegistration,
+    value: &dyn Reflect,
+    world: &World,
+) -> Option<Box<dyn Reflect>> {
+    let reflect_hooks = registration.data::<ReflectGraniteComponentHooks>()?;
+    let mut copy = registration
+        .data::<ReflectFromReflect>()?
+        .from_reflect(value.as_partial_reflect())?;
+    reflect_hooks.get_mut(copy.as_mut())?.on_before_save(world);
+    Some(copy)
+}
+
+/// Run on_after_load on every component of `entities` that has hooks
+pub fn run_after_load_hooks(world: &mut World, entities: &[Entity]) {
+    for &entity in entities {
+        // Hooks get the whole World, so the registry lock is not held while they run
+        let hooked: Vec<(
+            ReflectComponent,
+            ReflectGraniteComponentHooks,
+            ReflectFromReflect,
+        )> = {
+            let Ok(entity_ref) = world.get_entity(entity) else {
+                continue;
+            };
+            let type_registry = world.resource::<AppTypeRegistry>().read();
+            entity_ref
+                .archetype()
+                .components()
+                .filter_map(|component_id| world.components().get_info(component_id)?.type_id())
+                .filter_map(|type_id| {
+                    let registration = type_registry.get(type_id)?;
+                    Some((
+                        registration.data::<ReflectComponent>()?.clone(),
+                        registration.data::<ReflectGraniteComponentHooks>()?.clone(),
+                        registration.data::<ReflectFromReflect>()?.clone(),
+                    ))
+                })
+                .collect()
+        };
+
+        for (reflect_component, reflect_hooks, from_reflect) in hooked {
+            let Some(mut copy) = world
+                .get_entity(entity)
+                .ok()
+                .and_then(|entity_ref| reflect_component.reflect(entity_ref))
+                .and_then(|value| from_reflect.from_reflect(value.as_partial_reflect()))
+            else {
+                continue;
+            };
+            let Some(hooks) = reflect_hooks.get_mut(copy.as_mut()) else {
+                continue;
+            };
+            hooks.on_after_load(entity, world);
+
+            // The hook may have despawned the entity or removed the component
+            if let Ok(mut entity_mut) = world.get_entity_mut(entity) {
+                if reflect_component.contains(&entity_mut) {
+                    reflect_component.apply(&mut entity_mut, copy.as_partial_reflect());
+                }
+            }
+        }
+    }
+}
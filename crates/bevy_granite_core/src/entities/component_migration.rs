@@ -0,0 +1,153 @@
+// component_migration.rs
+// Versioned #[granite_component]s. Scenes record the version each component was saved at in
+// metadata.component_versions, older data is stepped up to the current version as ron::Value
+// before reflection deserializes it. Components without a recorded version count as version 1.
+//
+// ron::Value does not keep enum variant names, so top level fields a migration leaves untouched
+// are taken from the saved RON as is. Only fields it changed go through the Value.
+
+use super::serialize::scene_pretty_config;
+use bevy::reflect::{
+    serde::{TypedReflectDeserializer, TypedReflectSerializer},
+    FromType, TypeInfo, TypeRegistration, TypeRegistry,
+};
+use ron::value::RawValue;
+use serde::de::{DeserializeSeed, MapAccess, Visitor};
+use std::{collections::BTreeMap, fmt};
+
+/// Version and migrations of a #[granite_component]
+/// Usually generated by `#[granite_component(version = 2, migrate = my_migration)]`
+pub trait GraniteComponentMigration {
+    /// Version the component is saved at, start at 2 for the first change
+    const VERSION: u32;
+
+    /// Turn data saved at `from_version` into data for `from_version + 1`
+    /// Called once per version step, structs arrive as a map of field name to value
+    fn migrate(from_version: u32, value: ron::Value) -> Result<ron::Value, String>;
+}
+
+/// Type data for components with a GraniteComponentMigration, added by `register_editor_components!`
+#[derive(Clone)]
+pub struct ComponentMigrator {
+    pub version: u32,
+    pub migrate: fn(u32, ron::Value) -> Result<ron::Value, String>,
+}
+
+impl<T: GraniteComponentMigration> FromType<T> for ComponentMigrator {
+    fn from_type() -> Self {
+        Self {
+            version: T::VERSION,
+            migrate: T::migrate,
+        }
+    }
+}
+
+/// Bring component RON saved at `saved_version` up to the current version of the component
+/// Returns None when nothing had to change
+pub fn migrate_component_ron(
+    raw: &RawValue,
+    saved_version: u32,
+    registration: &TypeRegistration,
+    type_registry: &TypeRegistry,
+) -> Result<Option<String>, String> {
+    let Some(migrator) = registration.data::<ComponentMigrator>() else {
+        return Ok(None);
+    };
+    if saved_version >= migrator.version {
+        return Ok(None);
+    }
+
+    let saved: ron::Value = ron::from_str(raw.get_ron()).map_err(|e| e.to_string())?;
+    let mut value = saved.clone();
+    for from_version in saved_version..migrator.version {
+        value = (migrator.migrate)(from_version, value)
+            .map_err(|e| format!("migration from version {} failed: {}", from_version, e))?;
+    }
+
+    if let (TypeInfo::Struct(struct_info), ron::Value::Map(migrated), ron::Value::Map(saved)) =
+        (registration.type_info(), &value, &saved)
+    {
+        let saved_fields = raw_struct_fields(raw).map_err(|e| e.to_string())?;
+        let mut fields = Vec::new();
+        for (key, field_value) in migrated.iter() {
+            let ron::Value::String(name) = key else {
+                return Err(format!("field names must be strings, found {:?}", key));
+            };
+            let untouched = saved.get(key) == Some(field_value);
+            let ron = match saved_fields.get(name) {
+                Some(saved_ron) if untouched => saved_ron.get_ron().trim().to_string(),
+                _ => {
+                    let field = struct_info
+                        .field(name)
+                        .ok_or_else(|| format!("no field named '{}'", name))?;
+                    let field_registration = type_registry
+                        .get(field.type_id())
+                        .ok_or_else(|| format!("field '{}' has no registration", name))?;
+                    value_to_ron(field_value.clone(), field_registration, type_registry)?
+                }
+            };
+            fields.push(format!("{}: {}", name, ron));
+        }
+        return Ok(Some(format!("({})", fields.join(", "))));
+    }
+
+    value_to_ron(value, registration, type_registry).map(Some)
+}
+
+// Deserialize a Value as the registered type, then write it out as regular RON
+fn value_to_ron(
+    value: ron::Value,
+    registration: &TypeRegistration,
+    type_registry: &TypeRegistry,
+) -> Result<String, String> {
+    let reflected = TypedReflectDeserializer::new(registration, type_registry)
+        .deserialize(value)
+        .map_err(|e| e.to_string())?;
+    ron::ser::to_string_pretty(
+        &TypedReflectSerializer::new(reflected.as_partial_reflect(), type_registry),
+        scene_pretty_config(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+// Split a struct's RON into its fields, each kept as the RON it was written as
+fn raw_struct_fields(raw: &RawValue) -> ron::error::SpannedResult<BTreeMap<String, Box<RawValue>>> {
+    struct RawFields;
+
+    impl<'de> Visitor<'de> for RawFields {
+        type Value = BTreeMap<String, Box<RawValue>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a struct")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut fields = BTreeMap::new();
+            while let Some((name, value)) = map.next_entry::<String, Box<RawValue>>()? {
+                fields.insert(name, value);
+            }
+            Ok(fields)
+        }
+    }
+
+    // RON structs accept any field names when none are listed
+    let mut deserializer = ron::Deserializer::from_str(raw.get_ron())?;
+    serde::Deserializer::deserialize_struct(&mut deserializer, "", &[], RawFields)
+        .map_err(|e| deserializer.span_error(e))
+}
+
+/// Current version of every versioned component in `type_paths`, as saved into scene metadata
+pub fn current_component_versions<'a>(
+    type_paths: impl IntoIterator<Item = &'a String>,
+    type_registry: &TypeRegistry,
+) -> BTreeMap<String, u32> {
+    type_paths
+        .into_iter()
+        .filter_map(|type_path| {
+            let migrator = type_registry
+                .get_with_type_path(type_path)?
+                .data::<ComponentMigrator>()?;
+            Some((type_path.clone(), migrator.version))
+        })
+        .collect()
+}
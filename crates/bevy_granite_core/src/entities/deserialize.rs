@@ -1,22 +1,38 @@
-use super::{ComponentEditor, EntitySaveReadyData, IdentityData, SceneData, SpawnSource};
+use super::{
+    component_hooks::run_after_load_hooks, entity_ref::resolve_entity_refs,
+    serialize::dedent_raw_value, ComponentEditor, Empty, EntitySaveReadyData, GraniteTypes,
+    IdentityData, PendingWorldSpawns, PreservedSceneData, SceneData, SceneMetadata, SpawnSource,
+    TransformData,
+};
 use crate::{
-    absolute_asset_to_rel, materials_from_folder_into_scene, shared::is_scene_version_compatible,
+    absolute_asset_to_rel,
+    error::GraniteSceneError,
+    events::WorldSpawnedEvent,
+    materials_from_folder_into_scene,
+    shared::{
+        decode_scene_bytes, is_scene_version_compatible, read_scene_format_version,
+        rewrite_scene_file, SceneCompression, SceneMigrations,
+    },
     AvailableEditableMaterials, GraniteType,
 };
 use bevy::{
-    ecs::{entity::Entity, system::ResMut, world::World},
+    ecs::{
+        entity::Entity,
+        system::{ResMut, SystemParam},
+        world::World,
+    },
     pbr::StandardMaterial,
     prelude::{AppTypeRegistry, AssetServer, Assets, Commands, Component, Reflect, Res},
     render::mesh::Mesh,
+    transform::components::Transform,
 };
 use bevy_granite_logging::{
     config::{LogCategory, LogLevel, LogType},
     log,
 };
-use ron::de::from_str;
+use ron::{de::from_str, value::RawValue};
 use serde::{Deserialize, Serialize};
-use std::io::Read;
-use std::{borrow::Cow, fs::File};
+use std::{borrow::Cow, collections::BTreeMap};
 use uuid::Uuid;
 
 // Main component to tag all of our custom entity class types
@@ -24,57 +40,163 @@ use uuid::Uuid;
 #[derive(Component, Serialize, Reflect, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct GraniteEditorSerdeEntity;
 
+/// Asset resources the classes of a scene build their meshes and materials with
+#[derive(SystemParam)]
+pub struct SceneSpawnAssets<'w> {
+    pub asset_server: Res<'w, AssetServer>,
+    pub materials: ResMut<'w, Assets<StandardMaterial>>,
+    pub available_materials: ResMut<'w, AvailableEditableMaterials>,
+    pub meshes: ResMut<'w, Assets<Mesh>>,
+}
+
+/// Where entities spawned from a scene end up
+/// Only the scene's root entities are touched, children stay relative to their saved parents
+#[derive(Clone, Debug, Default)]
+pub struct SceneLoadOptions {
+    /// Scene root entities become children of this entity
+    pub parent: Option<Entity>,
+    /// Applied on top of the saved transform of every scene root entity
+    pub offset: Option<Transform>,
+    /// Give every spawned entity a new UUID, so the same scene can be loaded more than once
+    /// Parent links are remapped to the new UUIDs
+    pub regenerate_uuids: bool,
+}
+
+/// A scene held in memory instead of a file, e.g. built in code, from `include_str!` or the network
+#[derive(Debug)]
+pub enum InMemoryScene {
+    /// Already parsed or built in code, spawned as is
+    Data(SceneData),
+    /// Scene RON, migrated like a scene file would be
+    Ron(String),
+    /// Scene file bytes, plain or compressed
+    Bytes(Vec<u8>),
+}
+
+impl InMemoryScene {
+    /// Parse into SceneData, `source` only names the scene in errors
+    pub fn to_scene_data(
+        &self,
+        source: &str,
+        migrations: &SceneMigrations,
+    ) -> Result<SceneData, GraniteSceneError> {
+        match self {
+            InMemoryScene::Data(scene_data) => Ok(scene_data.clone()),
+            InMemoryScene::Ron(contents) => {
+                Ok(parse_scene_contents(contents, source, migrations)?.0)
+            }
+            InMemoryScene::Bytes(bytes) => {
+                let contents = decode_scene_bytes(bytes).map_err(|e| GraniteSceneError::Io {
+                    path: source.to_string(),
+                    source: e,
+                })?;
+                Ok(parse_scene_contents(&contents, source, migrations)?.0)
+            }
+        }
+    }
+}
+
 // Basically we grab the file contents into save ready struct
 // Spawn all entities - (might be able to improve and just insert components this step?)
 // Insert all components with access to mut World after all entities are spawned
 
 /// Build materials and entities into the scene from the world path
+/// Returns the scene's metadata so it can be kept around until the scene is saved again
 pub fn deserialize_entities(
-    asset_server: &Res<AssetServer>,
+    assets: &mut SceneSpawnAssets,
     commands: &mut Commands,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    available_materials: &mut ResMut<AvailableEditableMaterials>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    migrations: &SceneMigrations,
     abs_path: impl Into<Cow<'static, str>>, //absolute
-) {
+    options: &SceneLoadOptions,
+) -> Result<SceneMetadata, GraniteSceneError> {
     let abs_path: Cow<'static, str> = abs_path.into();
     // Build materials from the folder and load them into the scene
-    materials_from_folder_into_scene("materials", materials, available_materials, asset_server);
-
-    // Gather file contents into a Vec<EntitySaveReadyData>
-    let deserialized_data = gather_file_contents(
-        asset_server,
-        materials,
-        available_materials,
-        abs_path.as_ref(),
+    materials_from_folder_into_scene(
+        "materials",
+        &mut assets.materials,
+        &mut assets.available_materials,
+        &assets.asset_server,
     );
 
+    // Gather file contents into SceneData
+    let scene_data = gather_file_contents(assets, migrations, abs_path.as_ref())?;
+
+    let relative: Cow<'static, str> = absolute_asset_to_rel(abs_path.to_string());
+    spawn_scene_entities(
+        assets,
+        commands,
+        &scene_data.entities,
+        &scene_data.metadata.component_versions,
+        relative,
+        options,
+    );
+
+    Ok(scene_data.metadata)
+}
+
+/// Spawn already parsed scene entities, restore their hierarchy and queue their components
+/// Every spawned entity is tagged with `source` (relative path from /assets)
+/// A WorldSpawnedEvent is sent once all of it is applied
+/// `component_versions` comes from the scene metadata, older components are migrated on insert
+pub fn spawn_scene_entities(
+    assets: &mut SceneSpawnAssets,
+    commands: &mut Commands,
+    entities: &[EntitySaveReadyData],
+    component_versions: &BTreeMap<String, u32>,
+    source: Cow<'static, str>,
+    options: &SceneLoadOptions,
+) {
     // for id
     let mut uuid_to_entity_map: std::collections::HashMap<Uuid, Entity> =
         std::collections::HashMap::new();
-    let mut parent_relationships: Vec<(Entity, Uuid)> = Vec::new(); // (child_entity, parent_guid)
+    // (child_entity, parent_guid, saved transform)
+    let mut parent_relationships: Vec<(Entity, Uuid, Transform)> = Vec::new();
+    let mut root_entities: Vec<(Entity, Transform)> = Vec::new();
+    let mut spawned: Vec<Entity> = Vec::with_capacity(entities.len());
 
     // Deserialized data is Vec<EntitySaveReadyData>
-    for save_data in &deserialized_data {
-        let (entity, final_identity) = spawn_entity_from_class_type(
-            asset_server,
-            commands,
-            materials,
-            available_materials,
-            &mut meshes,
-            save_data,
-        );
+    for save_data in entities {
+        // Parents are still looked up by the saved UUID below, so they follow the regenerated ones
+        let regenerated;
+        let spawn_data = if options.regenerate_uuids {
+            regenerated = EntitySaveReadyData {
+                identity: IdentityData {
+                    uuid: Uuid::new_v4(),
+                    ..save_data.identity.clone()
+                },
+                transform: save_data.transform.clone(),
+                parent: save_data.parent,
+                components: None,
+                preserved_class: None,
+            };
+            &regenerated
+        } else {
+            save_data
+        };
+
+        let (entity, final_identity) = spawn_entity_from_class_type(assets, commands, spawn_data);
 
         // Map the stored GUID to the new entity
         uuid_to_entity_map.insert(save_data.identity.uuid, entity);
+        spawned.push(entity);
 
         // Tag entity with its source file
-        let relative: Cow<'static, str> = absolute_asset_to_rel(abs_path.to_string());
-        commands.entity(entity).insert(SpawnSource::new(relative));
+        commands
+            .entity(entity)
+            .insert(SpawnSource::new(source.clone()));
+
+        if let Some(raw_class) = save_data.preserved_class.as_ref() {
+            commands.entity(entity).insert(PreservedSceneData {
+                class: Some(raw_class.get_ron().to_string()),
+                ..Default::default()
+            });
+        }
 
         // Store parent relationships for second pass
         if let Some(parent_guid) = save_data.parent {
-            parent_relationships.push((entity, parent_guid));
+            parent_relationships.push((entity, parent_guid, save_data.transform.to_bevy()));
+        } else {
+            root_entities.push((entity, save_data.transform.to_bevy()));
         }
 
         log!(
@@ -96,6 +218,7 @@ pub fn deserialize_entities(
         // Load components into the scene entities
         if let Some(component_map) = save_data.components.as_ref() {
             let component_map = component_map.clone();
+            let component_versions = component_versions.clone();
             let entity_copy = entity;
 
             commands.queue(move |world: &mut World| {
@@ -108,6 +231,7 @@ pub fn deserialize_entities(
                         world,
                         entity_copy,
                         component_map,
+                        &component_versions,
                         type_registry,
                     );
 
@@ -118,7 +242,7 @@ pub fn deserialize_entities(
     }
 
     // Apply relationships
-    for (child_entity, parent_guid) in parent_relationships {
+    for (child_entity, parent_guid, saved_transform) in parent_relationships {
         if let Some(&parent_entity) = uuid_to_entity_map.get(&parent_guid) {
             commands.entity(parent_entity).add_child(child_entity);
             log!(
@@ -138,9 +262,38 @@ pub fn deserialize_entities(
                 parent_guid,
                 child_entity
             );
+            // Without its parent this is a root as far as placement goes
+            root_entities.push((child_entity, saved_transform));
         }
     }
 
+    // Place the scene roots
+    let roots: Vec<Entity> = root_entities.iter().map(|(root, _)| *root).collect();
+    for (root, saved_transform) in root_entities {
+        if let Some(offset) = options.offset {
+            commands
+                .entity(root)
+                .insert(offset.mul_transform(saved_transform));
+        }
+        if let Some(parent) = options.parent {
+            commands.entity(parent).add_child(root);
+        }
+    }
+
+    // Refs between entities of this scene and load hooks, once everything above is applied.
+    // WorldSpawnedEvent waits for the members of prefab instances, which spawn a frame later
+    let event = WorldSpawnedEvent {
+        source: source.to_string(),
+        entities: spawned,
+        uuid_to_entity: uuid_to_entity_map,
+        roots,
+    };
+    commands.queue(move |world: &mut World| {
+        resolve_entity_refs(world, &event.entities, &event.uuid_to_entity);
+        run_after_load_hooks(world, &event.entities);
+        world.resource_mut::<PendingWorldSpawns>().0.push(event);
+    });
+
     log!(
         LogType::Game,
         LogLevel::OK,
@@ -155,13 +308,12 @@ pub fn deserialize_entities(
     );
 }
 
-/// Gathers the file contents from the given path and deserializes them into EntitySaveReadyData
-fn gather_file_contents(
-    asset_server: &Res<AssetServer>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    available_materials: &mut ResMut<AvailableEditableMaterials>,
+/// Gathers the file contents from the given path and deserializes them into SceneData
+pub(crate) fn gather_file_contents(
+    assets: &mut SceneSpawnAssets,
+    migrations: &SceneMigrations,
     path: &str,
-) -> Vec<EntitySaveReadyData> {
+) -> Result<SceneData, GraniteSceneError> {
     log!(
         LogType::Game,
         LogLevel::Info,
@@ -181,34 +333,149 @@ fn gather_file_contents(
         "--------------------"
     );
 
-    let mut file = match File::open(&path) {
-        Ok(file) => file,
-        Err(e) => {
-            log!(
+    let io_error = |source| GraniteSceneError::Io {
+        path: path.to_string(),
+        source,
+    };
+
+    // Compressed scenes are detected by their magic bytes, not the extension
+    let file_bytes = std::fs::read(path).map_err(io_error)?;
+    let compression = SceneCompression::detect(&file_bytes);
+    let file_contents = decode_scene_bytes(&file_bytes).map_err(io_error)?;
+
+    let (scene_data, upgraded_contents) = parse_scene_contents(&file_contents, path, migrations)?;
+
+    if let Some(upgraded_contents) = upgraded_contents.filter(|_| migrations.upgrade_in_place) {
+        match compression
+            .compress(&upgraded_contents)
+            .and_then(|bytes| rewrite_scene_file(path, &bytes))
+        {
+            Ok(_) => log!(
+                LogType::Game,
+                LogLevel::OK,
+                LogCategory::System,
+                "Wrote upgraded scene back to: {}",
+                path
+            ),
+            Err(e) => log!(
                 LogType::Game,
                 LogLevel::Error,
                 LogCategory::System,
-                "Failed to open file {}: {}. Are you sure it exists?",
+                "Failed to write upgraded scene {}: {}",
                 path,
                 e
-            );
-            return vec![];
+            ),
         }
-    };
+    }
 
-    let mut file_contents = String::new();
-    if let Err(e) = file.read_to_string(&mut file_contents) {
-        log!(
-            LogType::Game,
-            LogLevel::Error,
-            LogCategory::System,
-            "Failed to read file {}: {}",
-            path,
-            e
+    if scene_data.entities.is_empty() {
+        // Still create materials even if no entities to deserialize
+        materials_from_folder_into_scene(
+            "materials",
+            &mut assets.materials,
+            &mut assets.available_materials,
+            &assets.asset_server,
         );
-        return vec![];
     }
 
+    Ok(scene_data)
+}
+
+/// Parse scene RON of the current format into SceneData
+/// Classes this app does not know are kept as raw RON in preserved_class, with an Empty class meanwhile
+pub fn parse_scene_data(path: &str, contents: &str) -> Result<SceneData, GraniteSceneError> {
+    let error = match from_str::<SceneData>(contents) {
+        Ok(scene_data) => return Ok(scene_data),
+        Err(e) => GraniteSceneError::from_ron(path, e),
+    };
+    if !matches!(error, GraniteSceneError::UnknownClass { .. }) {
+        return Err(error);
+    }
+
+    #[derive(Deserialize)]
+    struct RawClassIdentity {
+        uuid: Uuid,
+        name: String,
+        class: Box<RawValue>,
+    }
+
+    #[derive(Deserialize)]
+    struct RawClassEntity {
+        identity: RawClassIdentity,
+        transform: TransformData,
+        parent: Option<Uuid>,
+        components: Option<BTreeMap<String, Box<RawValue>>>,
+    }
+
+    #[derive(Deserialize)]
+    struct RawClassScene {
+        metadata: SceneMetadata,
+        entities: Vec<RawClassEntity>,
+    }
+
+    // Same scene with every class left as raw RON, then each class is parsed on its own
+    let Ok(raw_scene) = from_str::<RawClassScene>(contents) else {
+        return Err(error);
+    };
+
+    let mut entities = Vec::with_capacity(raw_scene.entities.len());
+    for entity in raw_scene.entities {
+        let raw_class = entity.identity.class;
+        let (class, preserved_class) = match from_str::<GraniteTypes>(raw_class.get_ron()) {
+            Ok(class) => (class, None),
+            Err(e) => match GraniteSceneError::from_ron(path, e) {
+                GraniteSceneError::UnknownClass { class, .. } => {
+                    log!(
+                        LogType::Game,
+                        LogLevel::Warning,
+                        LogCategory::Entity,
+                        "Unknown class '{}' on entity '{}' in {}, kept as is and loaded as an Empty",
+                        class,
+                        entity.identity.name,
+                        path
+                    );
+                    (
+                        GraniteTypes::Empty(Empty::default()),
+                        Some(dedent_raw_value(&raw_class)),
+                    )
+                }
+                class_error => return Err(class_error),
+            },
+        };
+
+        entities.push(EntitySaveReadyData {
+            identity: IdentityData {
+                uuid: entity.identity.uuid,
+                name: entity.identity.name,
+                class,
+            },
+            transform: entity.transform,
+            parent: entity.parent,
+            components: entity.components,
+            preserved_class,
+        });
+    }
+
+    Ok(SceneData {
+        metadata: raw_scene.metadata,
+        entities,
+    })
+}
+
+/// Migrate and parse raw scene RON into SceneData
+/// Shared by the file reader and the scene asset loader, so it never touches the disk itself.
+/// When migrations ran, the upgraded RON is returned alongside the scene
+/// Empty files give an empty scene with default metadata
+pub(crate) fn parse_scene_contents(
+    file_contents: &str,
+    path: &str,
+    migrations: &SceneMigrations,
+) -> Result<(SceneData, Option<String>), GraniteSceneError> {
+    let empty_scene = || SceneData {
+        metadata: SceneMetadata::default(),
+        entities: Vec::new(),
+    };
+
     // Handle empty file
     if file_contents.is_empty() {
         log!(
@@ -218,7 +485,7 @@ fn gather_file_contents(
             "No contents found in scene: {}",
             path
         );
-        return vec![];
+        return Ok((empty_scene(), None));
     }
 
     // Handle whitespace-only files
@@ -230,7 +497,7 @@ fn gather_file_contents(
             "Only whitespace found in scene: {}",
             path
         );
-        return vec![];
+        return Ok((empty_scene(), None));
     }
 
     // Handle empty JSON object or array
@@ -243,62 +510,70 @@ fn gather_file_contents(
             "Empty JSON structure found in scene: {}, skipping entity creation",
             path
         );
-        // Still create materials even if no entities to deserialize
-        materials_from_folder_into_scene("materials", materials, available_materials, asset_server);
-        return vec![];
+        return Ok((empty_scene(), None));
     }
 
-    // Attempt to deserialize with proper error handling
-    // Try new format first (with metadata), fallback to old format (direct array)
-    let deserialized_data: Vec<EntitySaveReadyData> = if let Ok(scene_data) =
-        from_str::<SceneData>(&file_contents)
-    {
+    // Bring older scene formats up to the current version before parsing
+    let migrated =
+        migrations
+            .migrate(file_contents)
+            .map_err(|message| GraniteSceneError::Version {
+                path: path.to_string(),
+                found: read_scene_format_version(file_contents).unwrap_or_default(),
+                message,
+            })?;
+    if migrated.was_migrated() {
         log!(
             LogType::Game,
             LogLevel::Info,
             LogCategory::System,
-            "Loading scene with metadata - Version: {}, Entities: {}",
-            scene_data.metadata.format_version,
-            scene_data.metadata.entity_count
+            "Upgraded scene {} from version {} ({})",
+            path,
+            migrated.original_version,
+            migrated.applied.join(", ")
         );
+    }
+    let was_migrated = migrated.was_migrated();
+    let file_contents = migrated.contents;
 
-        // Check version compatibility
-        if !is_scene_version_compatible(&scene_data.metadata.format_version) {
-            log!(
-                LogType::Game,
-                LogLevel::Warning,
-                LogCategory::System,
-                "Scene version {} may not be fully compatible with current version",
-                scene_data.metadata.format_version
-            );
-        }
+    // Attempt to deserialize with proper error handling
+    let scene_data = parse_scene_data(path, &file_contents)?;
 
-        let e_count = scene_data.entities.len();
-        if e_count != scene_data.metadata.entity_count {
-            log!(
-                    LogType::Game,
-                    LogLevel::Warning,
-                    LogCategory::System,
-                    "Entity count mismatch: expected {}, found {}. Not an error, but perhaps you manually edited the scene file?",
-                    scene_data.metadata.entity_count,
-                    e_count
-                );
-        }
+    log!(
+        LogType::Game,
+        LogLevel::Info,
+        LogCategory::System,
+        "Loading scene with metadata - Version: {}, Entities: {}",
+        scene_data.metadata.format_version,
+        scene_data.metadata.entity_count
+    );
 
-        scene_data.entities
-    } else {
+    // Check version compatibility
+    if !is_scene_version_compatible(&scene_data.metadata.format_version) {
         log!(
             LogType::Game,
-            LogLevel::Error,
+            LogLevel::Warning,
             LogCategory::System,
-            "Failed to deserialize data from {} - invalid format",
-            path
+            "Scene version {} may not be fully compatible with current version",
+            scene_data.metadata.format_version
         );
-        return vec![];
-    };
+    }
+
+    let e_count = scene_data.entities.len();
+    if e_count != scene_data.metadata.entity_count {
+        log!(
+                LogType::Game,
+                LogLevel::Warning,
+                LogCategory::System,
+                "Entity count mismatch: expected {}, found {}. Not an error, but perhaps you manually edited the scene file?",
+                scene_data.metadata.entity_count,
+                e_count
+            );
+    }
 
     // Handle case where deserialization succeeded but resulted in empty vector
-    if deserialized_data.is_empty() {
+    // Metadata is still kept, a scene can be described before it has entities
+    if scene_data.entities.is_empty() {
         log!(
             LogType::Game,
             LogLevel::Info,
@@ -306,9 +581,7 @@ fn gather_file_contents(
             "No entities found in scene: {}",
             path
         );
-        // Still create materials even if no entities
-        materials_from_folder_into_scene("materials", materials, available_materials, asset_server);
-        return vec![];
+        return Ok((scene_data, was_migrated.then_some(file_contents)));
     }
 
     log!(
@@ -316,29 +589,26 @@ fn gather_file_contents(
         LogLevel::Info,
         LogCategory::System,
         "Successfully loaded {} entities from scene: {}",
-        deserialized_data.len(),
+        scene_data.entities.len(),
         path
     );
-    deserialized_data
+    Ok((scene_data, was_migrated.then_some(file_contents)))
 }
 
 /// Spawns the entity and returns the identity data and entity
 fn spawn_entity_from_class_type(
-    asset_server: &Res<AssetServer>,
+    assets: &mut SceneSpawnAssets,
     commands: &mut Commands,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    available_materials: &mut ResMut<AvailableEditableMaterials>,
-    meshes: &mut ResMut<Assets<Mesh>>,
     save_data: &EntitySaveReadyData,
 ) -> (Entity, IdentityData) {
     let class = save_data.identity.class.clone();
     let entity = class.spawn_from_save_data(
         save_data,
         commands,
-        materials,
-        meshes,
-        available_materials,
-        asset_server,
+        &mut assets.materials,
+        &mut assets.meshes,
+        &mut assets.available_materials,
+        &assets.asset_server,
     );
 
     (entity, save_data.identity.clone())
@@ -39,6 +39,7 @@ pub struct RequestEntityUpdateFromClass<'w> {
     pub rectangle_brush: EventWriter<'w, UserUpdatedRectBrushEvent>,
     pub obj: EventWriter<'w, UserUpdatedOBJEvent>,
     pub empty: EventWriter<'w, UserUpdatedEmptyEvent>,
+    pub prefab: EventWriter<'w, UserUpdatedPrefabEvent>,
 }
 
 // ---------------------------------------------------------------------------------------
@@ -56,6 +57,7 @@ pub enum GraniteTypes {
     DirLight(DirLight),
     Camera3D(Camera3D),
     RectBrush(RectBrush),
+    Prefab(Prefab),
     Unknown(Unknown), // Holds no real data
 }
 impl GraniteTypes {
@@ -69,6 +71,7 @@ impl GraniteTypes {
             GraniteTypes::DirLight(Default::default()),
             GraniteTypes::Camera3D(Default::default()),
             GraniteTypes::RectBrush(Default::default()),
+            GraniteTypes::Prefab(Default::default()),
             GraniteTypes::Unknown(Default::default()),
         ]
     }
@@ -11,6 +11,7 @@ impl Plugin for ClassTypePlugin {
             .add_plugins(PointLightPlugin)
             .add_plugins(RectBrushPlugin)
             .add_plugins(EmptyPlugin)
+            .add_plugins(PrefabPlugin)
             .add_plugins(OBJPlugin);
     }
 }
@@ -0,0 +1,98 @@
+use super::{Prefab, PrefabPendingSpawn};
+use crate::{
+    absolute_asset_to_rel, entities::EntitySaveReadyData, GraniteEditorSerdeEntity, GraniteType,
+    GraniteTypes, HasRuntimeData, IdentityData, PromptData,
+};
+use bevy::{
+    ecs::{bundle::Bundle, entity::Entity, system::Commands},
+    prelude::Name,
+    render::view::Visibility,
+    transform::components::Transform,
+};
+use std::path::Path;
+use uuid::Uuid;
+
+impl Prefab {
+    /// Extract needed info to spawn this entity via save data
+    /// The source scene itself is spawned later by spawn_prefab_instances_system
+    pub fn spawn_from_save_data(
+        save_data: &EntitySaveReadyData,
+        commands: &mut Commands,
+    ) -> Entity {
+        let identity = &save_data.identity;
+        let save_transform = &save_data.transform;
+
+        Self::spawn_from_identity(commands, identity, save_transform.to_bevy())
+    }
+
+    /// Take the name and class from identity to spawn
+    pub fn spawn_from_identity(
+        commands: &mut Commands,
+        identity: &IdentityData,
+        transform: Transform,
+    ) -> Entity {
+        let class = Self::extract_class(identity);
+
+        class.spawn(identity, commands, transform)
+    }
+
+    /// Generally to be used from UI popups - spawns with new identity
+    /// In this case the maybe_prompt_data is the prompted .scene file
+    pub fn spawn_from_new_identity(
+        &mut self,
+        commands: &mut Commands,
+        transform: Transform,
+        maybe_prompt_data: Option<PromptData>,
+    ) -> Entity {
+        let prompt_info = maybe_prompt_data.unwrap_or_default();
+        let file_path = prompt_info.file.unwrap_or(self.scene_path.to_string());
+
+        self.scene_path = absolute_asset_to_rel(file_path);
+        self.overrides.clear();
+
+        let entity_name = Path::new(self.scene_path.as_ref())
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.type_name());
+
+        let identity = IdentityData {
+            name: entity_name,
+            uuid: Uuid::new_v4(),
+            class: GraniteTypes::Prefab(self.clone()),
+        };
+        self.spawn(&identity, commands, transform)
+    }
+
+    /// Private core logic
+    fn spawn(
+        &self,
+        identity: &IdentityData,
+        commands: &mut Commands,
+        transform: Transform,
+    ) -> Entity {
+        commands
+            .spawn(Self::get_bundle(identity.clone(), transform))
+            .id()
+    }
+
+    /// Build a bundle that is ready to spawn from a prefab
+    /// The instance itself is a spatial root, its scene entities become children of it
+    fn get_bundle(identity: IdentityData, transform: Transform) -> impl Bundle {
+        (
+            Visibility::default(),
+            transform,
+            Name::new(identity.name.clone()),
+            HasRuntimeData,
+            GraniteEditorSerdeEntity,
+            PrefabPendingSpawn,
+            identity,
+        )
+    }
+
+    fn extract_class(identity: &IdentityData) -> Prefab {
+        match &identity.class {
+            GraniteTypes::Prefab(prefab_data) => prefab_data.clone(),
+            _ => panic!("Expected Prefab class data, got different type from save data"),
+        }
+    }
+}
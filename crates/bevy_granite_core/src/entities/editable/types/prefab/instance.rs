@@ -0,0 +1,549 @@
+use super::{Prefab, PrefabMember, PrefabOverride, PrefabPendingSpawn};
+use crate::{
+    entities::{
+        resolve_entity_refs, run_after_load_hooks,
+        serialize::{indent_raw_value, round_quat, round_vec3, CLASS_INDENT_DEPTH},
+        ComponentEditor, EntitySaveReadyData, SceneSpawnAssets, TransformData,
+    },
+    events::{WorldSaveSuccessEvent, WorldSpawnedEvent},
+    shared::absolute_asset_to_rel,
+    world::{GraniteSceneAsset, SceneMetadataStore},
+    GraniteType, GraniteTypes, IdentityData, SpawnSource,
+};
+use bevy::{
+    asset::{Assets, Handle, LoadState},
+    ecs::{
+        entity::Entity,
+        event::{EventReader, EventWriter},
+        query::With,
+        resource::Resource,
+        system::{Commands, Query, Res, ResMut},
+        world::World,
+    },
+    prelude::AppTypeRegistry,
+    transform::components::Transform,
+};
+use bevy_granite_logging::{log, LogCategory, LogLevel, LogType};
+use ron::value::RawValue;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+// Override components sit this deep inside the parent scene file
+// (class -> prefab fields -> overrides -> override -> components map)
+const OVERRIDE_COMPONENT_INDENT_DEPTH: usize = CLASS_INDENT_DEPTH + 4;
+
+// Transforms closer than this to the source are not treated as overrides
+const TRANSFORM_EPSILON: f32 = 0.0005;
+
+/// Instance entities get UUIDs derived from the instance and the source entity
+/// so they stay the same across reloads and never collide between instances
+pub fn prefab_member_uuid(instance_uuid: Uuid, source_uuid: Uuid) -> Uuid {
+    Uuid::new_v5(&instance_uuid, source_uuid.as_bytes())
+}
+
+/// WorldSpawnedEvents of loaded scenes, held back until their prefab instances have spawned
+/// their members, which are added to the event as they appear
+#[derive(Resource, Debug, Default)]
+pub struct PendingWorldSpawns(pub Vec<WorldSpawnedEvent>);
+
+/// Source scenes of prefabs by scene path, loaded once through the asset server and shared by
+/// every instance. Handles are kept so a source stays loaded while its instances come and go
+#[derive(Resource, Debug, Default)]
+pub struct PrefabSources(pub HashMap<String, Handle<GraniteSceneAsset>>);
+
+/// Reload the source of saved prefabs, so instances spawned afterwards get the saved version
+pub fn reload_prefab_sources_system(
+    mut saved_reader: EventReader<WorldSaveSuccessEvent>,
+    sources: Res<PrefabSources>,
+    assets: SceneSpawnAssets,
+) {
+    for WorldSaveSuccessEvent(path) in saved_reader.read() {
+        let path = absolute_asset_to_rel(path.clone());
+        if sources.0.contains_key(path.as_ref()) {
+            assets.asset_server.reload(path.to_string());
+        }
+    }
+}
+
+/// Send the WorldSpawnedEvents whose scenes have no prefab instance left waiting to spawn
+pub fn send_world_spawned_system(
+    mut pending: ResMut<PendingWorldSpawns>,
+    pending_prefabs: Query<(), With<PrefabPendingSpawn>>,
+    mut spawned_writer: EventWriter<WorldSpawnedEvent>,
+) {
+    if pending.0.is_empty() {
+        return;
+    }
+
+    let (ready, waiting): (Vec<_>, Vec<_>) = pending.0.drain(..).partition(|event| {
+        !event
+            .entities
+            .iter()
+            .any(|entity| pending_prefabs.contains(*entity))
+    });
+    pending.0 = waiting;
+    spawned_writer.write_batch(ready);
+}
+
+/// Spawn the source scene of every prefab instance that is waiting for it
+/// Overrides stored on the instance are applied on top of the source data
+/// Sources are loaded through the asset server, instances wait until theirs has loaded
+pub fn spawn_prefab_instances_system(
+    mut commands: Commands,
+    pending_query: Query<(Entity, &IdentityData, Option<&SpawnSource>), With<PrefabPendingSpawn>>,
+    ancestor_query: Query<(&IdentityData, Option<&PrefabMember>)>,
+    mut assets: SceneSpawnAssets,
+    scene_assets: Res<Assets<GraniteSceneAsset>>,
+    mut sources: ResMut<PrefabSources>,
+    metadata_store: Res<SceneMetadataStore>,
+) {
+    for (instance, identity, spawn_source) in pending_query.iter() {
+        let GraniteTypes::Prefab(prefab) = &identity.class else {
+            commands.entity(instance).remove::<PrefabPendingSpawn>();
+            continue;
+        };
+
+        if prefab.scene_path.is_empty() {
+            commands.entity(instance).remove::<PrefabPendingSpawn>();
+            log!(
+                LogType::Game,
+                LogLevel::Warning,
+                LogCategory::Entity,
+                "Prefab '{}' has no scene path, nothing to spawn",
+                identity.name
+            );
+            continue;
+        }
+
+        // A prefab that contains itself (directly or through other prefabs) would never stop spawning
+        let outer_instance = |entity| {
+            ancestor_query
+                .get(entity)
+                .ok()
+                .and_then(|(_, member)| member.map(|m| m.instance))
+        };
+        let mut ancestor = outer_instance(instance);
+        let mut cyclic = false;
+        while let Some(outer) = ancestor {
+            if let Ok((
+                IdentityData {
+                    class: GraniteTypes::Prefab(outer_prefab),
+                    ..
+                },
+                _,
+            )) = ancestor_query.get(outer)
+            {
+                if outer_prefab.scene_path == prefab.scene_path {
+                    cyclic = true;
+                    break;
+                }
+            }
+            ancestor = outer_instance(outer);
+        }
+        if cyclic {
+            commands.entity(instance).remove::<PrefabPendingSpawn>();
+            log!(
+                LogType::Game,
+                LogLevel::Error,
+                LogCategory::Entity,
+                "Prefab '{}' references '{}' inside itself, skipping",
+                identity.name,
+                prefab.scene_path
+            );
+            continue;
+        }
+
+        let handle = sources
+            .0
+            .entry(prefab.scene_path.to_string())
+            .or_insert_with(|| assets.asset_server.load(prefab.scene_path.to_string()))
+            .clone();
+        let Some(source_scene) = scene_assets.get(&handle) else {
+            if let LoadState::Failed(error) = assets.asset_server.load_state(&handle) {
+                // Dropped so the next instance tries again, e.g. once the file is fixed
+                sources.0.remove(prefab.scene_path.as_ref());
+                commands.entity(instance).remove::<PrefabPendingSpawn>();
+                log!(
+                    LogType::Game,
+                    LogLevel::Error,
+                    LogCategory::Entity,
+                    "Failed to spawn prefab '{}': {}",
+                    identity.name,
+                    error
+                );
+            }
+            continue;
+        };
+
+        commands.entity(instance).remove::<PrefabPendingSpawn>();
+        spawn_prefab_members(
+            &mut commands,
+            &mut assets,
+            PrefabInstance {
+                entity: instance,
+                uuid: identity.uuid,
+                prefab,
+                spawn_source,
+            },
+            &source_scene.entities,
+            PrefabComponentVersions {
+                source: &source_scene.metadata.component_versions,
+                overrides: spawn_source
+                    .and_then(|source| metadata_store.get(source.str_ref()))
+                    .map(|metadata| &metadata.component_versions),
+            },
+        );
+    }
+}
+
+// The instance whose source scene is being spawned
+struct PrefabInstance<'a> {
+    entity: Entity,
+    uuid: Uuid,
+    prefab: &'a Prefab,
+    spawn_source: Option<&'a SpawnSource>,
+}
+
+// Source components were saved with the source scene, overrides with the scene holding the instance
+struct PrefabComponentVersions<'a> {
+    source: &'a BTreeMap<String, u32>,
+    overrides: Option<&'a BTreeMap<String, u32>>,
+}
+
+fn spawn_prefab_members(
+    commands: &mut Commands,
+    assets: &mut SceneSpawnAssets,
+    instance: PrefabInstance,
+    source_entities: &[EntitySaveReadyData],
+    component_versions: PrefabComponentVersions,
+) {
+    let PrefabInstance {
+        entity: instance,
+        uuid: instance_uuid,
+        prefab,
+        spawn_source,
+    } = instance;
+    let mut source_to_entity: HashMap<Uuid, Entity> = HashMap::new();
+    let mut members: Vec<(Uuid, Entity)> = Vec::new();
+    let mut parent_relationships: Vec<(Entity, Option<Uuid>)> = Vec::new();
+
+    for source in source_entities {
+        let source_uuid = source.identity.uuid;
+        let source_components = source.components.clone().unwrap_or_default();
+        let overrides = prefab.overrides.get(&source_uuid);
+
+        let override_components = overrides
+            .map(|overrides| overrides.components.clone())
+            .unwrap_or_default();
+        let mut components = source_components.clone();
+        components.retain(|name, _| !override_components.contains_key(name));
+
+        let save_data = EntitySaveReadyData {
+            identity: IdentityData {
+                uuid: prefab_member_uuid(instance_uuid, source_uuid),
+                ..source.identity.clone()
+            },
+            transform: overrides
+                .and_then(|o| o.transform.clone())
+                .unwrap_or_else(|| source.transform.clone()),
+            parent: None,
+            components: None,
+            preserved_class: None,
+        };
+
+        let class = save_data.identity.class.clone();
+        let entity = class.spawn_from_save_data(
+            &save_data,
+            commands,
+            &mut assets.materials,
+            &mut assets.meshes,
+            &mut assets.available_materials,
+            &assets.asset_server,
+        );
+
+        commands.entity(entity).insert(PrefabMember {
+            instance,
+            source_uuid,
+            source_transform: source.transform.clone(),
+            source_components,
+        });
+        if let Some(spawn_source) = spawn_source {
+            commands.entity(entity).insert(spawn_source.clone());
+        }
+
+        if !components.is_empty() || !override_components.is_empty() {
+            let source_versions = component_versions.source.clone();
+            let override_versions = component_versions.overrides.cloned().unwrap_or_default();
+            commands.queue(move |world: &mut World| {
+                let type_registry = world.resource::<AppTypeRegistry>().clone();
+
+                // Remove the resource to avoid borrowing errors
+                if let Some(component_editor) = world.remove_resource::<ComponentEditor>() {
+                    component_editor.load_components_from_scene_data(
+                        world,
+                        entity,
+                        components,
+                        &source_versions,
+                        type_registry.clone(),
+                    );
+                    component_editor.load_components_from_scene_data(
+                        world,
+                        entity,
+                        override_components,
+                        &override_versions,
+                        type_registry,
+                    );
+
+                    world.insert_resource(component_editor);
+                }
+            });
+        }
+
+        source_to_entity.insert(source_uuid, entity);
+        members.push((save_data.identity.uuid, entity));
+        parent_relationships.push((entity, source.parent));
+    }
+
+    // Refs between members were saved with the source uuids, load hooks run once they are set.
+    // The members then join the WorldSpawnedEvent of the scene the instance was loaded with
+    let source_uuids = source_to_entity.clone();
+    commands.queue(move |world: &mut World| {
+        let entities: Vec<Entity> = members.iter().map(|(_, entity)| *entity).collect();
+        resolve_entity_refs(world, &entities, &source_uuids);
+        run_after_load_hooks(world, &entities);
+
+        let mut pending = world.resource_mut::<PendingWorldSpawns>();
+        if let Some(event) = pending
+            .0
+            .iter_mut()
+            .find(|event| event.entities.contains(&instance))
+        {
+            event.entities.extend(entities);
+            event.uuid_to_entity.extend(members);
+        }
+    });
+
+    // Source roots hang off the instance, everything else keeps its source hierarchy
+    for (entity, parent_uuid) in parent_relationships {
+        let parent = parent_uuid
+            .and_then(|uuid| source_to_entity.get(&uuid).copied())
+            .unwrap_or(instance);
+        commands.entity(parent).add_child(entity);
+    }
+
+    log!(
+        LogType::Game,
+        LogLevel::OK,
+        LogCategory::Entity,
+        "Spawned prefab '{}' with {} entities",
+        prefab.scene_path,
+        source_to_entity.len()
+    );
+}
+
+/// Work out the overrides of every prefab instance in `entities` from the current state of its members
+/// The new class data is returned for saving, the instances themselves are left as they are
+pub fn collect_prefab_overrides(
+    world: &mut World,
+    entities: &[Entity],
+) -> HashMap<Entity, GraniteTypes> {
+    let mut collected = HashMap::new();
+    let Some(component_editor) = world.get_resource::<ComponentEditor>().cloned() else {
+        return collected;
+    };
+
+    let mut member_query = world.query::<(Entity, &PrefabMember, &Transform)>();
+    let members: Vec<(Entity, PrefabMember, Transform)> = member_query
+        .iter(world)
+        .map(|(entity, member, transform)| (entity, member.clone(), *transform))
+        .collect();
+
+    for &instance in entities {
+        let Some(IdentityData {
+            class: GraniteTypes::Prefab(prefab),
+            ..
+        }) = world.get::<IdentityData>(instance).cloned()
+        else {
+            continue;
+        };
+
+        let mut overrides = BTreeMap::new();
+        for (entity, member, transform) in members.iter().filter(|(_, m, _)| m.instance == instance)
+        {
+            let member_override = member_override(
+                member,
+                transform,
+                component_editor.serialize_entity_components(world, *entity),
+            );
+            if !member_override.is_empty() {
+                overrides.insert(member.source_uuid, member_override);
+            }
+        }
+
+        collected.insert(
+            instance,
+            GraniteTypes::Prefab(Prefab {
+                scene_path: prefab.scene_path.clone(),
+                overrides,
+            }),
+        );
+    }
+
+    collected
+}
+
+// What a member differs in from its source entity, given its current transform and components
+fn member_override(
+    member: &PrefabMember,
+    transform: &Transform,
+    components: BTreeMap<String, Box<RawValue>>,
+) -> PrefabOverride {
+    let mut member_override = PrefabOverride::default();
+
+    let current = TransformData {
+        position: round_vec3(transform.translation),
+        rotation: round_quat(transform.rotation),
+        scale: round_vec3(transform.scale),
+    };
+    if !transform_matches(&current, &member.source_transform) {
+        member_override.transform = Some(current);
+    }
+
+    for (name, raw) in components {
+        let unchanged = member
+            .source_components
+            .get(&name)
+            .is_some_and(|source| same_ron(source, &raw));
+        if !unchanged {
+            member_override.components.insert(
+                name,
+                indent_raw_value(&raw, OVERRIDE_COMPONENT_INDENT_DEPTH),
+            );
+        }
+    }
+
+    member_override
+}
+
+fn transform_matches(a: &TransformData, b: &TransformData) -> bool {
+    a.position.abs_diff_eq(b.position, TRANSFORM_EPSILON)
+        && a.rotation.abs_diff_eq(b.rotation, TRANSFORM_EPSILON)
+        && a.scale.abs_diff_eq(b.scale, TRANSFORM_EPSILON)
+}
+
+// Compare two RON values while ignoring formatting. Anything that is not byte-for-byte
+// the same once whitespace is gone counts as changed, which at worst stores a redundant override
+fn same_ron(a: &RawValue, b: &RawValue) -> bool {
+    normalize_ron(a.get_ron()) == normalize_ron(b.get_ron())
+}
+
+fn normalize_ron(ron: &str) -> String {
+    let mut normalized = String::with_capacity(ron.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in ron.chars() {
+        if in_string {
+            normalized.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                normalized.push(c);
+            }
+            ')' | ']' | '}' => {
+                // Trailing commas are optional
+                if normalized.ends_with(',') {
+                    normalized.pop();
+                }
+                normalized.push(c);
+            }
+            c if c.is_whitespace() => {}
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::Vec3;
+
+    fn ron(value: &str) -> Box<RawValue> {
+        RawValue::from_boxed_ron(value.into()).unwrap()
+    }
+
+    // Member of a source entity at the origin with a single component
+    fn member(component: &str) -> PrefabMember {
+        PrefabMember {
+            instance: Entity::PLACEHOLDER,
+            source_uuid: Uuid::from_u128(1),
+            source_transform: TransformData {
+                position: Vec3::ZERO,
+                rotation: Default::default(),
+                scale: Vec3::ONE,
+            },
+            source_components: BTreeMap::from([("game::Health".to_string(), ron(component))]),
+        }
+    }
+
+    #[test]
+    fn normalize_ron_ignores_whitespace_and_trailing_commas() {
+        assert_eq!(
+            normalize_ron("(\n\tvalue: 10,\n\tlist: [1, 2,],\n)"),
+            "(value:10,list:[1,2])"
+        );
+    }
+
+    #[test]
+    fn normalize_ron_keeps_strings() {
+        assert_eq!(normalize_ron(r#"( name: "a b,)" )"#), r#"(name:"a b,)")"#);
+        assert_eq!(normalize_ron(r#"("a\" b" )"#), r#"("a\" b")"#);
+    }
+
+    #[test]
+    fn same_ron_compares_values() {
+        assert!(same_ron(&ron("(value: 10)"), &ron("(\n\tvalue: 10,\n)")));
+        assert!(!same_ron(&ron("(value: 10)"), &ron("(value: 11)")));
+        assert!(!same_ron(&ron(r#"(name: "a b")"#), &ron(r#"(name: "ab")"#)));
+    }
+
+    #[test]
+    fn unchanged_member_has_no_override() {
+        let member = member("(value: 10)");
+        let components = BTreeMap::from([("game::Health".to_string(), ron("(\n\tvalue: 10,\n)"))]);
+        assert!(member_override(&member, &Transform::IDENTITY, components).is_empty());
+    }
+
+    #[test]
+    fn moved_member_overrides_its_transform() {
+        let member = member("(value: 10)");
+        let components = BTreeMap::from([("game::Health".to_string(), ron("(value: 10)"))]);
+        let moved = Transform::from_xyz(1.0, 0.0, 0.0);
+        let member_override = member_override(&member, &moved, components);
+        assert_eq!(member_override.transform.map(|t| t.position), Some(Vec3::X));
+        assert!(member_override.components.is_empty());
+    }
+
+    #[test]
+    fn changed_and_added_components_are_overrides() {
+        let member = member("(value: 10)");
+        let components = BTreeMap::from([
+            ("game::Health".to_string(), ron("(value: 5)")),
+            ("game::Armor".to_string(), ron("(value: 1)")),
+        ]);
+        let member_override = member_override(&member, &Transform::IDENTITY, components);
+        assert!(member_override.transform.is_none());
+        assert_eq!(
+            member_override.components.keys().collect::<Vec<_>>(),
+            ["game::Armor", "game::Health"]
+        );
+    }
+}
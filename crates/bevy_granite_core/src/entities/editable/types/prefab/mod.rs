@@ -0,0 +1,141 @@
+use crate::{
+    entities::{
+        editable::{GraniteType, RequestEntityUpdateFromClass},
+        EntitySaveReadyData, TransformData,
+    },
+    AvailableEditableMaterials, ClassCategory, PromptData,
+};
+use bevy::{
+    asset::{AssetServer, Assets},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::Event,
+        system::{Commands, Res, ResMut},
+    },
+    pbr::StandardMaterial,
+    reflect::Reflect,
+    render::mesh::Mesh,
+    transform::components::Transform,
+};
+use bevy_egui::egui;
+use ron::value::RawValue;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, collections::BTreeMap};
+use uuid::Uuid;
+
+pub mod creation;
+pub mod instance;
+pub mod plugin;
+pub mod ui;
+pub mod update_event;
+
+pub use instance::{
+    collect_prefab_overrides, prefab_member_uuid, reload_prefab_sources_system,
+    send_world_spawned_system, spawn_prefab_instances_system, PendingWorldSpawns, PrefabSources,
+};
+pub use plugin::*;
+pub use update_event::*;
+
+/// Internal event thats called when user edits UI prefab variables
+#[derive(Event)]
+pub struct UserUpdatedPrefabEvent {
+    pub entity: Entity,
+    pub data: Prefab,
+}
+
+/// Actual serialized class data thats stored inside IdentityData
+/// scene_path is relative disk path to the .scene this instance spawns
+/// overrides only hold what differs from the source scene, keyed by the source entity UUID
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, PartialEq, Default)]
+pub struct Prefab {
+    pub scene_path: Cow<'static, str>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[reflect(ignore)]
+    pub overrides: BTreeMap<Uuid, PrefabOverride>,
+}
+
+/// Per-instance changes to a single entity of the source scene
+/// Components are replaced whole, components removed from an instance are not tracked
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PrefabOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<TransformData>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, Box<RawValue>>,
+}
+
+impl PrefabOverride {
+    pub fn is_empty(&self) -> bool {
+        self.transform.is_none() && self.components.is_empty()
+    }
+}
+
+/// Tag for prefab entities whose source scene still needs to be spawned under them
+#[derive(Component)]
+pub struct PrefabPendingSpawn;
+
+/// Entity spawned from a prefab's source scene
+/// These are never saved as entities themselves, only their differences end up in the instance overrides
+#[derive(Component, Clone, Debug)]
+pub struct PrefabMember {
+    pub instance: Entity,
+    pub source_uuid: Uuid,
+    pub source_transform: TransformData,
+    pub source_components: BTreeMap<String, Box<RawValue>>,
+}
+
+impl GraniteType for Prefab {
+    fn category(&self) -> ClassCategory {
+        ClassCategory::Gameplay
+    }
+
+    fn type_name(&self) -> String {
+        "Prefab".to_string()
+    }
+
+    fn type_abv(&self) -> String {
+        "Prefab".to_string()
+    }
+
+    fn needs_prompt(&self) -> bool {
+        true
+    }
+
+    fn get_prompt_config(&self) -> (String, Vec<&'static str>) {
+        ("scenes".to_string(), vec!["scene", "zst", "gz"])
+    }
+
+    fn spawn_from_new_identity(
+        &mut self,
+        commands: &mut Commands,
+        transform: Transform,
+        _standard_materials: ResMut<Assets<StandardMaterial>>,
+        _meshes: ResMut<Assets<Mesh>>,
+        _available_materials: ResMut<AvailableEditableMaterials>,
+        _asset_server: Res<AssetServer>,
+        maybe_prompt_data: Option<PromptData>,
+    ) -> Entity {
+        self.spawn_from_new_identity(commands, transform, maybe_prompt_data)
+    }
+
+    fn spawn_from_save_data(
+        &self,
+        save_data: &EntitySaveReadyData,
+        commands: &mut Commands,
+        _standard_materials: &mut ResMut<Assets<StandardMaterial>>,
+        _meshes: &mut ResMut<Assets<Mesh>>,
+        _available_materials: &mut ResMut<AvailableEditableMaterials>,
+        _asset_server: &Res<AssetServer>,
+    ) -> Entity {
+        Prefab::spawn_from_save_data(save_data, commands)
+    }
+
+    fn push_to_entity(&self, entity: Entity, request_update: &mut RequestEntityUpdateFromClass) {
+        self.push_to_entity(entity, request_update)
+    }
+
+    fn edit_via_ui(&mut self, ui: &mut egui::Ui, spacing: (f32, f32, f32)) -> bool {
+        self.edit_via_ui(ui, spacing)
+    }
+}
@@ -0,0 +1,42 @@
+use super::{
+    reload_prefab_sources_system, send_world_spawned_system, spawn_prefab_instances_system,
+    update_prefab_system, PendingWorldSpawns, PrefabSources, UserUpdatedPrefabEvent,
+};
+use crate::entities::Prefab;
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::schedule::IntoScheduleConfigs,
+};
+
+pub struct PrefabPlugin;
+impl Plugin for PrefabPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            //
+            // Event
+            //
+            .add_event::<UserUpdatedPrefabEvent>()
+            //
+            // Register
+            //
+            .register_type::<Prefab>()
+            //
+            // Resources
+            //
+            .init_resource::<PendingWorldSpawns>()
+            .init_resource::<PrefabSources>()
+            //
+            // Schedule system
+            //
+            .add_systems(
+                Update,
+                (
+                    reload_prefab_sources_system,
+                    update_prefab_system,
+                    spawn_prefab_instances_system,
+                    send_world_spawned_system,
+                )
+                    .chain(),
+            );
+    }
+}
@@ -0,0 +1,56 @@
+use super::Prefab;
+use crate::GraniteType;
+use bevy_egui::egui;
+
+impl Prefab {
+    /// Function to edit self's data via UI side panel
+    /// We have a sister system that pushes changes to world entity - can be found inside 'update_event.rs'
+    /// When true, the instance is respawned from its source scene
+    pub fn edit_via_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        // Small, Large, Normal
+        spacing: (f32, f32, f32),
+    ) -> bool {
+        let large_spacing = spacing.1;
+        ui.label(egui::RichText::new(self.type_name()).italics());
+        ui.add_space(large_spacing);
+
+        let mut changed = false;
+        egui::Grid::new("prefab_settings_grid")
+            .num_columns(2)
+            .spacing([large_spacing, large_spacing])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Scene:");
+                ui.label(self.scene_path.as_ref());
+                ui.end_row();
+                ui.label("Overrides:");
+                ui.label(self.overrides.len().to_string());
+                ui.end_row();
+            });
+        ui.add_space(large_spacing);
+
+        ui.horizontal(|ui| {
+            if ui
+                .button("Reload from source")
+                .on_hover_text("Respawn this instance from its scene file, unsaved edits are lost")
+                .clicked()
+            {
+                changed = true;
+            }
+            if ui
+                .add_enabled(
+                    !self.overrides.is_empty(),
+                    egui::Button::new("Clear overrides"),
+                )
+                .clicked()
+            {
+                self.overrides.clear();
+                changed = true;
+            }
+        });
+
+        changed
+    }
+}
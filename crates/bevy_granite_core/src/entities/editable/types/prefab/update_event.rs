@@ -0,0 +1,64 @@
+use super::{Prefab, PrefabMember, PrefabPendingSpawn, UserUpdatedPrefabEvent};
+use crate::entities::editable::RequestEntityUpdateFromClass;
+use bevy::{
+    ecs::{
+        entity::Entity,
+        event::EventReader,
+        system::{Commands, Query},
+    },
+    prelude::ChildOf,
+};
+use bevy_granite_logging::{log, LogCategory, LogLevel, LogType};
+
+impl Prefab {
+    pub fn push_to_entity(
+        &self,
+        entity: Entity,
+        request_update: &mut RequestEntityUpdateFromClass,
+    ) {
+        log!(
+            LogType::Editor,
+            LogLevel::Info,
+            LogCategory::Entity,
+            "Requesting prefab entity update"
+        );
+
+        request_update.prefab.write(UserUpdatedPrefabEvent {
+            entity,
+            data: self.clone(),
+        });
+    }
+}
+
+/// Actually update the specific entity with the class data
+/// The identity already holds the new class data, so we drop the spawned scene and let it respawn
+pub fn update_prefab_system(
+    mut commands: Commands,
+    mut reader: EventReader<UserUpdatedPrefabEvent>,
+    member_query: Query<(Entity, &PrefabMember, &ChildOf)>,
+) {
+    for UserUpdatedPrefabEvent {
+        entity: requested_entity,
+        data: _new,
+    } in reader.read()
+    {
+        log!(
+            LogType::Editor,
+            LogLevel::Info,
+            LogCategory::Entity,
+            "Heard prefab update event: {}",
+            requested_entity
+        );
+
+        // Only the top level members, despawn takes their children with them
+        for (member, _, _) in member_query.iter().filter(|(_, member, child_of)| {
+            member.instance == *requested_entity && child_of.parent() == *requested_entity
+        }) {
+            commands.entity(member).despawn();
+        }
+
+        commands
+            .entity(*requested_entity)
+            .insert(PrefabPendingSpawn);
+    }
+}
@@ -0,0 +1,340 @@
+// entity_ref.rs
+// Entity ids only live for one session, so a granite component pointing at another entity holds
+// a GraniteEntityRef instead. It is written to the scene as the target's IdentityData uuid, saving
+// looks the uuid up through a map that is only set while components are being collected. Loaded
+// refs only know the uuid until resolve_entity_refs finds the entity, right after the scene's
+// components are inserted or later on, when the target comes from a source loaded afterwards.
+// Entities still waiting on a target are kept in UnresolvedEntityRefs, so only those are retried
+
+use super::{ComponentEditor, GraniteUuidIndex, IdentityData};
+use bevy::{
+    ecs::{entity::Entity, world::World},
+    prelude::{
+        Added, AppTypeRegistry, Commands, Query, ReflectComponent, ReflectDefault,
+        ReflectDeserialize, ReflectSerialize, Res, Resource,
+    },
+    reflect::{PartialReflect, Reflect, ReflectMut, ReflectRef},
+};
+use bevy_granite_logging::{
+    config::{LogCategory, LogLevel, LogType},
+    log,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+use uuid::Uuid;
+
+/// Saved uuid -> target entity and its current uuid
+type RefTargets = HashMap<Uuid, (Entity, Uuid)>;
+
+thread_local! {
+    static SAVED_UUIDS: RefCell<Option<HashMap<Entity, Uuid>>> = const { RefCell::new(None) };
+}
+
+/// Entities whose loaded granite components hold refs to targets that are not loaded yet
+#[derive(Resource, Debug, Clone, Default)]
+pub struct UnresolvedEntityRefs(HashSet<Entity>);
+
+impl UnresolvedEntityRefs {
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.0.contains(&entity)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Link from a granite component to another entity that survives saving and loading
+/// Saved as the target's IdentityData uuid, `get` is None until the target is loaded
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[reflect(opaque)]
+#[reflect(Serialize, Deserialize, Default, PartialEq, Debug, Hash)]
+pub struct GraniteEntityRef {
+    entity: Option<Entity>,
+    uuid: Option<Uuid>,
+}
+
+impl GraniteEntityRef {
+    /// Point at an entity, it needs IdentityData to be saved
+    pub fn new(entity: Entity) -> Self {
+        Self {
+            entity: Some(entity),
+            uuid: None,
+        }
+    }
+
+    /// Point at the entity with this uuid once it is loaded
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self {
+            entity: None,
+            uuid: Some(uuid),
+        }
+    }
+
+    /// Target entity, None when empty or not resolved yet
+    pub fn get(&self) -> Option<Entity> {
+        self.entity
+    }
+
+    /// Uuid of the target as last loaded or resolved
+    pub fn uuid(&self) -> Option<Uuid> {
+        self.uuid
+    }
+
+    /// Point at another entity, or nothing
+    pub fn set(&mut self, entity: Option<Entity>) {
+        self.entity = entity;
+        self.uuid = None;
+    }
+
+    /// Waiting for its target to be loaded
+    pub fn is_unresolved(&self) -> bool {
+        self.entity.is_none() && self.uuid.is_some()
+    }
+}
+
+impl Serialize for GraniteEntityRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let saved = self.entity.and_then(|entity| {
+            SAVED_UUIDS.with_borrow(|uuids| uuids.as_ref()?.get(&entity).copied())
+        });
+        if let (Some(entity), None) = (self.entity, saved) {
+            // Outside with_entity_uuids, or the target has no IdentityData
+            log!(
+                LogType::Game,
+                LogLevel::Warning,
+                LogCategory::Entity,
+                "Entity ref to {:?} has no uuid to write, writing {:?} instead",
+                entity,
+                self.uuid
+            );
+        }
+        saved.or(self.uuid).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GraniteEntityRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Option::<Uuid>::deserialize(deserializer)?
+            .map(Self::from_uuid)
+            .unwrap_or_default())
+    }
+}
+
+/// Run `f` with entity refs saved as the uuid of their target
+/// Everything that serializes components for a scene goes through here
+pub fn with_entity_uuids<R>(world: &mut World, f: impl FnOnce(&mut World) -> R) -> R {
+    let uuids = world.resource::<GraniteUuidIndex>().entity_uuids().clone();
+    let previous = SAVED_UUIDS.replace(Some(uuids));
+    let result = f(world);
+    SAVED_UUIDS.set(previous);
+    result
+}
+
+/// Point unresolved entity refs in the granite components of `entities` at their targets
+/// `scene_uuids` maps saved uuids to the entities just spawned for them, checked before the uuids
+/// currently in the world so a scene loaded with regenerated uuids still links to itself.
+/// Entities left with refs whose target is missing go into UnresolvedEntityRefs
+pub fn resolve_entity_refs(
+    world: &mut World,
+    entities: &[Entity],
+    scene_uuids: &HashMap<Uuid, Entity>,
+) {
+    let index = world.resource::<GraniteUuidIndex>();
+    let target_of = |uuid: &Uuid| {
+        let entity = scene_uuids
+            .get(uuid)
+            .copied()
+            .or_else(|| index.get(*uuid))?;
+        Some((entity, index.uuid_of(entity)?))
+    };
+
+    // Look first so only components that change are borrowed mutably
+    let mut pending: Vec<(Entity, ReflectComponent, RefTargets)> = Vec::new();
+    let mut waiting = HashSet::new();
+    {
+        let type_registry = world.resource::<AppTypeRegistry>().read();
+        let Some(component_editor) = world.get_resource::<ComponentEditor>() else {
+            return;
+        };
+        for &entity in entities {
+            let Ok(entity_world) = world.get_entity(entity) else {
+                continue;
+            };
+            for component_id in entity_world.archetype().components() {
+                let Some(registration) = world
+                    .components()
+                    .get_info(component_id)
+                    .and_then(|info| type_registry.get(info.type_id()?))
+                else {
+                    continue;
+                };
+                if component_editor.should_skip_component(registration) {
+                    continue;
+                }
+                let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+                    continue;
+                };
+                let Some(value) = reflect_component.reflect(entity_world) else {
+                    continue;
+                };
+
+                let mut targets = HashMap::new();
+                visit_entity_refs(value.as_partial_reflect(), &mut |entity_ref| {
+                    if let Some(uuid) = entity_ref.uuid.filter(|_| entity_ref.is_unresolved()) {
+                        match target_of(&uuid) {
+                            Some(target) => {
+                                targets.insert(uuid, target);
+                            }
+                            None => {
+                                waiting.insert(entity);
+                            }
+                        }
+                    }
+                });
+                if !targets.is_empty() {
+                    pending.push((entity, reflect_component.clone(), targets));
+                }
+            }
+        }
+    }
+
+    if let Some(mut unresolved) = world.get_resource_mut::<UnresolvedEntityRefs>() {
+        for entity in entities {
+            if waiting.contains(entity) {
+                unresolved.0.insert(*entity);
+            } else if unresolved.contains(*entity) {
+                unresolved.0.remove(entity);
+            }
+        }
+    }
+
+    for (entity, reflect_component, targets) in pending {
+        let Some(mut value) = reflect_component.reflect_mut(world.entity_mut(entity)) else {
+            continue;
+        };
+        visit_entity_refs_mut(value.as_partial_reflect_mut(), &mut |entity_ref| {
+            if let Some(&(target, uuid)) = entity_ref.uuid.and_then(|uuid| targets.get(&uuid)) {
+                if entity_ref.entity.is_none() {
+                    entity_ref.entity = Some(target);
+                    entity_ref.uuid = Some(uuid);
+                }
+            }
+        });
+        log!(
+            LogType::Game,
+            LogLevel::Info,
+            LogCategory::Entity,
+            "Resolved entity refs of {:?}",
+            entity
+        );
+    }
+}
+
+/// Refs can point at entities from sources loaded later, so retry whenever identities show up
+pub fn resolve_entity_refs_system(
+    added_query: Query<(), Added<IdentityData>>,
+    unresolved: Res<UnresolvedEntityRefs>,
+    mut commands: Commands,
+) {
+    if unresolved.is_empty() || added_query.is_empty() {
+        return;
+    }
+    let entities: Vec<Entity> = unresolved.0.iter().copied().collect();
+    commands.queue(move |world: &mut World| {
+        resolve_entity_refs(world, &entities, &HashMap::new());
+    });
+}
+
+fn visit_entity_refs(value: &dyn PartialReflect, visit: &mut dyn FnMut(&GraniteEntityRef)) {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => value
+            .iter_fields()
+            .for_each(|f| visit_entity_refs(f, visit)),
+        ReflectRef::TupleStruct(value) => value
+            .iter_fields()
+            .for_each(|f| visit_entity_refs(f, visit)),
+        ReflectRef::Tuple(value) => value
+            .iter_fields()
+            .for_each(|f| visit_entity_refs(f, visit)),
+        ReflectRef::List(value) => value.iter().for_each(|f| visit_entity_refs(f, visit)),
+        ReflectRef::Array(value) => value.iter().for_each(|f| visit_entity_refs(f, visit)),
+        ReflectRef::Map(value) => value.iter().for_each(|(_, f)| visit_entity_refs(f, visit)),
+        ReflectRef::Enum(value) => value
+            .iter_fields()
+            .for_each(|f| visit_entity_refs(f.value(), visit)),
+        ReflectRef::Opaque(value) => {
+            if let Some(entity_ref) = value.try_downcast_ref::<GraniteEntityRef>() {
+                visit(entity_ref);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn visit_entity_refs_mut(
+    value: &mut dyn PartialReflect,
+    visit: &mut dyn FnMut(&mut GraniteEntityRef),
+) {
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for i in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(i) {
+                    visit_entity_refs_mut(field, visit);
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for i in 0..value.field_len() {
+                if let Some(field) = value.field_mut(i) {
+                    visit_entity_refs_mut(field, visit);
+                }
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for i in 0..value.field_len() {
+                if let Some(field) = value.field_mut(i) {
+                    visit_entity_refs_mut(field, visit);
+                }
+            }
+        }
+        ReflectMut::List(value) => {
+            for i in 0..value.len() {
+                if let Some(item) = value.get_mut(i) {
+                    visit_entity_refs_mut(item, visit);
+                }
+            }
+        }
+        ReflectMut::Array(value) => {
+            for i in 0..value.len() {
+                if let Some(item) = value.get_mut(i) {
+                    visit_entity_refs_mut(item, visit);
+                }
+            }
+        }
+        ReflectMut::Map(value) => {
+            let keys: Vec<_> = value.iter().map(|(key, _)| key.to_dynamic()).collect();
+            for key in keys {
+                if let Some(item) = value.get_mut(key.as_ref()) {
+                    visit_entity_refs_mut(item, visit);
+                }
+            }
+        }
+        ReflectMut::Enum(value) => {
+            for i in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(i) {
+                    visit_entity_refs_mut(field, visit);
+                }
+            }
+        }
+        ReflectMut::Opaque(value) => {
+            if let Some(entity_ref) = value.try_downcast_mut::<GraniteEntityRef>() {
+                visit(entity_ref);
+            }
+        }
+        _ => {}
+    }
+}
@@ -7,6 +7,10 @@ use bevy_granite_logging::{
 
 use bevy::prelude::{Commands, Entity, EventReader, Query, With};
 
+/// Serializable entities with the source they were spawned from
+pub type SpawnSourceQuery<'w, 's> =
+    Query<'w, 's, (Entity, &'static SpawnSource), With<IdentityData>>;
+
 /// If entity has IdentityData, it is despawned
 pub fn despawn_entities_system(
     mut despawn_watcher: EventReader<RequestDespawnSerializableEntities>,
@@ -29,17 +33,10 @@ pub fn despawn_entities_system(
 pub fn despawn_entities_by_source_system(
     mut despawn_watcher: EventReader<RequestDespawnBySource>,
     mut commands: Commands,
-    serializable_query: Query<(Entity, &SpawnSource), With<IdentityData>>,
+    serializable_query: SpawnSourceQuery,
 ) {
     for RequestDespawnBySource(source) in despawn_watcher.read() {
-        let mut despawned_count = 0;
-
-        for (entity, entity_source) in serializable_query.iter() {
-            if entity_source.0 == *source {
-                commands.entity(entity).despawn();
-                despawned_count += 1;
-            }
-        }
+        let despawned_count = despawn_source_entities(&mut commands, &serializable_query, source);
 
         log!(
             LogType::Editor,
@@ -61,3 +58,20 @@ pub fn despawn_recursive_serializable_entities(
         commands.entity(entity).despawn();
     }
 }
+
+/// Despawn every serializable entity of a source, returns how many there were
+/// The source stays loaded as far as the editor is concerned, unlike with RequestDespawnBySource
+pub fn despawn_source_entities(
+    commands: &mut Commands,
+    source_query: &SpawnSourceQuery,
+    source: &str,
+) -> usize {
+    let mut despawned_count = 0;
+    for (entity, entity_source) in source_query.iter() {
+        if entity_source.str_ref() == source {
+            commands.entity(entity).despawn();
+            despawned_count += 1;
+        }
+    }
+    despawned_count
+}
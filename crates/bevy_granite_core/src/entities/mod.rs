@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeMap};
 
 use bevy::{
     ecs::component::Component,
@@ -14,12 +14,16 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 pub mod component_editor;
+pub mod component_hooks;
+pub mod component_migration;
 pub mod deserialize;
 pub mod editable;
+pub mod entity_ref;
 pub mod generate_tangents;
 pub mod lifecycle;
 pub mod plugin;
 pub mod serialize;
+pub mod uuid_index;
 pub use editable::*;
 
 /// Main camera
@@ -63,6 +67,19 @@ pub struct TreeHiddenEntity;
 #[reflect(Component, Serialize, Deserialize, Default, FromReflect)]
 pub struct InternalNote(pub String);
 
+/// Scene data this app could not load, kept as raw RON so saving writes it back untouched
+/// Happens when another tool saved the scene with classes or components this app lacks
+#[derive(Reflect, Debug, Clone, Component, Default, PartialEq)]
+#[reflect(Component, Default)]
+pub struct PreservedSceneData {
+    /// Class RON when the class is unknown, the entity is an Empty meanwhile
+    pub class: Option<String>,
+    /// Component type path -> component RON, for components that could not be loaded
+    pub components: BTreeMap<String, String>,
+    /// Version the scene saved each of those components at, they are written back at it
+    pub component_versions: BTreeMap<String, u32>,
+}
+
 // --------------------------------------------------------------------------------------------
 
 //
@@ -162,17 +179,36 @@ impl Default for PromptData {
 pub use component_editor::{
     is_bridge_component_check, BridgeTag, ComponentEditor, ExposedToEditor, ReflectedComponent,
 };
-pub use deserialize::{deserialize_entities, GraniteEditorSerdeEntity};
+pub use component_hooks::{
+    prepare_component_for_save, run_after_load_hooks, GraniteComponentHooks,
+    ReflectGraniteComponentHooks,
+};
+pub use component_migration::{
+    current_component_versions, migrate_component_ron, ComponentMigrator,
+    GraniteComponentMigration,
+};
+pub use deserialize::{
+    deserialize_entities, parse_scene_data, spawn_scene_entities, GraniteEditorSerdeEntity,
+    InMemoryScene, SceneLoadOptions, SceneSpawnAssets,
+};
 pub use editable::{
     Camera3D, DirLight, Empty, GraniteTypes, PointLightData, RectBrush, VolumetricFog, OBJ,
 };
+pub use entity_ref::{
+    resolve_entity_refs, resolve_entity_refs_system, with_entity_uuids, GraniteEntityRef,
+    UnresolvedEntityRefs,
+};
 pub use generate_tangents::{generate_tangents_system, NeedsTangents};
 pub use lifecycle::{
     despawn_entities_by_source_system, despawn_entities_system,
-    despawn_recursive_serializable_entities,
+    despawn_recursive_serializable_entities, despawn_source_entities, SpawnSourceQuery,
 };
 pub use plugin::EntityPlugin;
-pub use serialize::{serialize_entities, EntitySaveReadyData, SceneData, SceneMetadata};
+pub use serialize::{
+    format_scene_data, metadata_timestamp, serialize_entities, EntitySaveReadyData, SceneData,
+    SceneMetadata,
+};
+pub use uuid_index::{sync_uuid_index_system, GraniteUuidIndex, IndexedEntity};
 
 // Im adding this so you cant select the editor camera
 // and to stop a crash because you can select a gizmo that then despawns its self
@@ -1,9 +1,17 @@
 use super::{
-    despawn_entities_system, despawn_entities_by_source_system, generate_tangents_system, BridgeTag, ComponentEditor, HasRuntimeData,
-    IdentityData, InternalNote, MainCamera, SpawnSource, UICamera
+    uuid_index::{
+        index_identity_insert_observer, index_identity_replace_observer,
+        index_source_insert_observer, index_source_replace_observer,
+    },
+    despawn_entities_system, despawn_entities_by_source_system, generate_tangents_system, resolve_entity_refs_system, sync_uuid_index_system, BridgeTag, GraniteEntityRef, GraniteUuidIndex, ComponentEditor, HasRuntimeData,
+    IdentityData, InternalNote, MainCamera, PreservedSceneData, SpawnSource, UICamera,
+    UnresolvedEntityRefs,
 };
 use crate::entities::{editable::ClassTypePlugin, PromptImportSettings};
-use bevy::app::{App, Plugin, Update};
+use bevy::{
+    app::{App, Plugin, PostUpdate, Update},
+    prelude::IntoScheduleConfigs,
+};
 
 pub struct EntityPlugin;
 impl Plugin for EntityPlugin {
@@ -25,14 +33,26 @@ impl Plugin for EntityPlugin {
             .register_type_data::<InternalNote, BridgeTag>()
             .register_type::<IdentityData>()
             .register_type::<HasRuntimeData>()
+            .register_type::<PreservedSceneData>()
+            .register_type::<GraniteEntityRef>()
             //
             // Resources
             //
             .insert_resource(ComponentEditor::default())
             .insert_resource(PromptImportSettings::default())
+            .init_resource::<GraniteUuidIndex>()
+            .init_resource::<UnresolvedEntityRefs>()
+            //
+            // Observers
+            //
+            .add_observer(index_identity_insert_observer)
+            .add_observer(index_identity_replace_observer)
+            .add_observer(index_source_insert_observer)
+            .add_observer(index_source_replace_observer)
             //
             // Schedule system
             //
-            .add_systems(Update, (despawn_entities_system, despawn_entities_by_source_system, generate_tangents_system));
+            .add_systems(Update, (despawn_entities_system, despawn_entities_by_source_system, generate_tangents_system))
+            .add_systems(PostUpdate, (sync_uuid_index_system, resolve_entity_refs_system).chain());
     }
 }
@@ -1,66 +1,211 @@
-use super::{IdentityData, TransformData};
-use crate::{get_current_scene_version, world::WorldState};
+use super::{GraniteTypes, IdentityData, TransformData};
+use crate::{
+    error::GraniteSceneError,
+    get_current_scene_version,
+    shared::{write_scene_file, SceneCompression},
+    world::{SceneSaveSettings, WorldState},
+};
 use bevy::prelude::{Quat, Vec3};
 use bevy_granite_logging::{
     config::{LogCategory, LogLevel, LogType},
     log,
 };
 
-use ron::ser::{to_string_pretty, PrettyConfig};
-use serde::{Deserialize, Serialize};
+use ron::{
+    ser::{to_string_pretty, PrettyConfig},
+    value::RawValue,
+};
+use serde::{Deserialize, Serialize, Serializer};
 use std::{
-    collections::HashMap,
-    fs::{self, File},
-    io::Write,
-    path::Path,
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    hash::Hash,
 };
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct SceneMetadata {
     pub format_version: String,
     pub entity_count: usize,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// RFC 3339, set when the scene is first written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    /// RFC 3339, set whenever a save actually changes the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<String>,
+
+    /// Game specific data, e.g. level name, music track or gravity
+    /// Sorted by key so unchanged scenes still save to identical files
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, ron::Value>,
+
+    /// Saved version of each versioned component, components not listed are at version 1
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub component_versions: BTreeMap<String, u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl Default for SceneMetadata {
+    fn default() -> Self {
+        Self {
+            format_version: get_current_scene_version(),
+            entity_count: 0,
+            description: None,
+            author: None,
+            tags: Vec::new(),
+            created: None,
+            modified: None,
+            custom: BTreeMap::new(),
+            component_versions: BTreeMap::new(),
+        }
+    }
+}
+
+/// Current time the way scene metadata stores it
+pub fn metadata_timestamp() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SceneData {
     pub metadata: SceneMetadata,
     pub entities: Vec<EntitySaveReadyData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct EntitySaveReadyData {
     pub identity: IdentityData,
     pub transform: TransformData,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<Uuid>, // Parent entity UUID, needs to be universal if other worlds are loaded in. Bevy id not good enough
+    pub components: Option<BTreeMap<String, Box<RawValue>>>, // Component type path -> component RON
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub components: Option<HashMap<String, String>>,
+    /// Class RON this app does not know, written in place of identity.class
+    /// identity.class is an Empty stand-in meanwhile
+    #[serde(skip)]
+    pub preserved_class: Option<Box<RawValue>>,
 }
 
-// want set order or something? only actually save to disk if things changed. Same with editor toml
-pub fn serialize_entities(world_state: WorldState, path: Option<String>) {
-    let entities_data = world_state.entity_data;
-    let runtime_data_provider = world_state.component_data.unwrap_or_default();
+impl EntitySaveReadyData {
+    /// Variant name of the preserved class, e.g. `Foliage` for `Foliage((density: 2.0))`
+    pub fn preserved_class_name(&self) -> Option<&str> {
+        self.preserved_class.as_ref().map(|raw| {
+            let ron = raw.get_ron().trim_start();
+            let end = ron.find(|c: char| c == '(' || c.is_whitespace());
+            &ron[..end.unwrap_or(ron.len())]
+        })
+    }
+}
+
+// Written by hand so a preserved class goes back exactly where identity.class would be
+impl Serialize for EntitySaveReadyData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(untagged)]
+        enum SavedClass<'a> {
+            Known(&'a GraniteTypes),
+            Preserved(&'a RawValue),
+        }
+
+        #[derive(Serialize)]
+        struct SavedIdentity<'a> {
+            uuid: &'a Uuid,
+            name: &'a str,
+            class: SavedClass<'a>,
+        }
+
+        #[derive(Serialize)]
+        struct SavedEntity<'a> {
+            identity: SavedIdentity<'a>,
+            transform: &'a TransformData,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            parent: &'a Option<Uuid>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            components: &'a Option<BTreeMap<String, Box<RawValue>>>,
+        }
 
-    // Map entity indices to their actual UUIDs from IdentityData
-    let mut entity_uuid_map = std::collections::HashMap::new();
-    if let Some(entity_vec) = &entities_data {
-        for (entity, identity, _, _) in entity_vec.iter() {
-            entity_uuid_map.insert(entity.index(), identity.uuid);
+        SavedEntity {
+            identity: SavedIdentity {
+                uuid: &self.identity.uuid,
+                name: &self.identity.name,
+                class: match &self.preserved_class {
+                    Some(raw) => SavedClass::Preserved(raw),
+                    None => SavedClass::Known(&self.identity.class),
+                },
+            },
+            transform: &self.transform,
+            parent: &self.parent,
+            components: &self.components,
         }
+        .serialize(serializer)
     }
+}
+
+// Components are nested this many levels deep inside the scene file
+// (scene -> entity -> components map, entities array is compact)
+const COMPONENT_INDENT_DEPTH: usize = 3;
+// Same for the class (scene -> entity -> identity -> class)
+pub(crate) const CLASS_INDENT_DEPTH: usize = 3;
+
+/// Pretty config shared by the scene file and the component values nested inside it
+pub(crate) fn scene_pretty_config() -> PrettyConfig {
+    PrettyConfig::new()
+        .depth_limit(15)
+        .separate_tuple_members(false)
+        .enumerate_arrays(false)
+        .compact_arrays(true)
+        .indentor("\t".to_string())
+}
+
+// Raw values are written verbatim, so line them up with the surrounding scene indentation
+pub(crate) fn indent_raw_value(raw: &RawValue, depth: usize) -> Box<RawValue> {
+    let indent = "\t".repeat(depth);
+    let indented = raw
+        .get_ron()
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    RawValue::from_boxed_ron(indented.into_boxed_str()).unwrap_or_else(|_| raw.to_owned())
+}
+
+/// Build the scene written for a world state, ordered as a save would write it
+/// metadata gets the current version, entity count and component versions
+pub(crate) fn scene_data_from_world_state(
+    world_state: WorldState,
+    metadata: &mut SceneMetadata,
+) -> SceneData {
+    let entities_data = world_state.entity_data.map(|entities| {
+        sort_by_hierarchy(entities, |(entity, identity, _, parent)| {
+            (*entity, identity.uuid, *parent)
+        })
+    });
+    let runtime_data_provider = world_state.component_data.unwrap_or_default();
 
     let entities_to_serialize: Vec<EntitySaveReadyData> = match &entities_data {
         Some(entity_vec) => entity_vec
             .iter()
-            .map(|(entity, identity, transform, parent)| {
+            .map(|(entity, identity, transform, _)| {
                 let translation = round_vec3(transform.translation);
                 let rotation = round_quat(transform.rotation);
                 let scale = round_vec3(transform.scale);
-                let parent_uuid = parent.and_then(|p| entity_uuid_map.get(&p.index()).copied());
                 EntitySaveReadyData {
                     identity: identity.clone(),
                     transform: TransformData {
@@ -68,51 +213,98 @@ pub fn serialize_entities(world_state: WorldState, path: Option<String>) {
                         rotation,
                         scale,
                     },
-                    parent: parent_uuid, 
-                    components: runtime_data_provider.get(entity).cloned(),
+                    parent: world_state.parent_uuids.get(entity).copied(),
+                    components: runtime_data_provider.get(entity).map(|components| {
+                        components
+                            .iter()
+                            .map(|(name, raw)| {
+                                (name.clone(), indent_raw_value(raw, COMPONENT_INDENT_DEPTH))
+                            })
+                            .collect()
+                    }),
+                    preserved_class: world_state
+                        .preserved_classes
+                        .get(entity)
+                        .map(|raw| indent_raw_value(raw, CLASS_INDENT_DEPTH)),
                 }
             })
             .collect(),
         None => Vec::new(),
     };
 
-    let pretty_config = PrettyConfig::new()
-        .depth_limit(15)
-        .separate_tuple_members(false)
-        .enumerate_arrays(false)
-        .compact_arrays(true)
-        .indentor("\t".to_string());
+    metadata.format_version = get_current_scene_version();
+    metadata.entity_count = entities_to_serialize.len();
+    metadata
+        .component_versions
+        .extend(world_state.component_versions);
+
+    SceneData {
+        metadata: metadata.clone(),
+        entities: entities_to_serialize,
+    }
+}
+
+/// Write the world state to disk as a scene file
+/// Entities are ordered parents first then by UUID, components by type path, so unchanged worlds produce identical files
+/// metadata is written as is apart from version, entity count and timestamps, which are updated in place
+pub fn serialize_entities(
+    world_state: WorldState,
+    path: Option<String>,
+    metadata: &mut SceneMetadata,
+    settings: &SceneSaveSettings,
+) -> Result<(), GraniteSceneError> {
+    let pretty_config = scene_pretty_config();
 
     if let Some(path) = path {
-        // Create metadata with version from TOML file
-        let metadata = SceneMetadata {
-            format_version: get_current_scene_version(),
-            entity_count: entities_to_serialize.len(),
+        // Scenes written before metadata had timestamps get theirs on their next save
+        if metadata.created.is_none() {
+            metadata.created = Some(metadata_timestamp());
+        }
+
+        // Version from TOML file, everything else carries over from when the scene was loaded
+        let mut scene_data = scene_data_from_world_state(world_state, metadata);
+
+        let serialize = |scene_data: &SceneData| {
+            to_string_pretty(scene_data, pretty_config.clone()).map_err(|e| {
+                GraniteSceneError::Serialize {
+                    path: path.clone(),
+                    message: e.to_string(),
+                }
+            })
         };
+        let mut serialized_data = serialize(&scene_data)?;
 
-        // Wrap entities with metadata
-        let scene_data = SceneData {
-            metadata,
-            entities: entities_to_serialize,
+        let io_error = |source| GraniteSceneError::Io {
+            path: path.clone(),
+            source,
         };
 
-        let serialized_data = to_string_pretty(&scene_data, pretty_config).unwrap();
+        let compression = settings
+            .compression
+            .unwrap_or_else(|| SceneCompression::from_path(&path));
+        let mut file_bytes = compression.compress(&serialized_data).map_err(io_error)?;
 
-        // TODO:
-        // Compress the data (Encrypt?)
-        // (and uncompressor)
-        let mut file = {
-            // Create parent directories first
-            if let Some(parent) = Path::new(&path).parent() {
-                fs::create_dir_all(parent)
-                    .unwrap_or_else(|_| panic!("Failed to create directories for path: {}", path));
-            }
+        // Only actually save to disk if things changed
+        if settings.skip_unchanged && fs::read(&path).is_ok_and(|existing| existing == file_bytes) {
+            log!(
+                LogType::Game,
+                LogLevel::Info,
+                LogCategory::System,
+                "Scene unchanged, skipped writing: '{}'",
+                path
+            );
+            return Ok(());
+        }
 
-            File::create(&path).unwrap_or_else(|_| panic!("Failed to create file: {}", path))
-        };
+        // Only stamp the modified time once we know the file changes, so it does not cause a change itself
+        scene_data.metadata.modified = Some(metadata_timestamp());
+        serialized_data = serialize(&scene_data)?;
+        metadata.modified = scene_data.metadata.modified;
+        file_bytes = compression.compress(&serialized_data).map_err(io_error)?;
 
-        file.write_all(serialized_data.as_bytes())
-            .expect("Failed to write to file");
+        // TODO:
+        // Encrypt?
+        write_scene_file(&path, &file_bytes, settings.backups).map_err(io_error)?;
 
         log!(
             LogType::Game,
@@ -128,16 +320,146 @@ pub fn serialize_entities(world_state: WorldState, path: Option<String>) {
             "-------------"
         );
     }
+
+    Ok(())
+}
+
+/// Pretty print already parsed scene data the same way a save writes it, no app needed
+/// Entities are put parents first, components re-indented and entity_count set to the real count
+pub fn format_scene_data(mut scene: SceneData) -> Result<String, ron::Error> {
+    scene.entities = sort_by_hierarchy(scene.entities, |entity| {
+        (entity.identity.uuid, entity.identity.uuid, entity.parent)
+    });
+    for entity in scene.entities.iter_mut() {
+        if let Some(components) = entity.components.as_mut() {
+            for raw in components.values_mut() {
+                *raw = indent_raw_value(&dedent_raw_value(raw), COMPONENT_INDENT_DEPTH);
+            }
+        }
+        if let Some(raw) = entity.preserved_class.as_mut() {
+            *raw = indent_raw_value(&dedent_raw_value(raw), CLASS_INDENT_DEPTH);
+        }
+    }
+    scene.metadata.entity_count = scene.entities.len();
+
+    to_string_pretty(&scene, scene_pretty_config())
+}
+
+/// Drop whatever indentation a raw value was written with, as if it was serialized on its own
+pub(crate) fn dedent_raw_value(raw: &RawValue) -> Box<RawValue> {
+    let ron = raw.trim().get_ron();
+    let base = ron
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let dedented = ron
+        .lines()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => line,
+            _ => line.get(base..).unwrap_or(line.trim_start()),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    RawValue::from_boxed_ron(dedented.into_boxed_str()).unwrap_or_else(|_| raw.to_owned())
+}
+
+// Depth first walk so every parent is written before its children.
+// Roots and siblings are ordered by UUID. node gives (key, uuid, parent key) of an item
+fn sort_by_hierarchy<T, K: Copy + Eq + Hash>(
+    mut entities: Vec<T>,
+    node: impl Fn(&T) -> (K, Uuid, Option<K>),
+) -> Vec<T> {
+    entities.sort_by_key(|entity| node(entity).1);
+
+    let saved: HashSet<K> = entities.iter().map(|entity| node(entity).0).collect();
+    let mut children: HashMap<K, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (index, entity) in entities.iter().enumerate() {
+        let (_, _, parent) = node(entity);
+        match parent.filter(|p| saved.contains(p)) {
+            Some(parent) => children.entry(parent).or_default().push(index),
+            None => roots.push(index),
+        }
+    }
+
+    // Visited by index, so duplicate keys and parent cycles can not walk forever or repeat items.
+    // Anything not reachable from a root is part of a parent cycle, walked in UUID order after them
+    let mut visited = vec![false; entities.len()];
+    let mut order = Vec::with_capacity(entities.len());
+    for start in roots.into_iter().chain(0..entities.len()) {
+        let mut stack = vec![start];
+        while let Some(index) = stack.pop() {
+            if std::mem::replace(&mut visited[index], true) {
+                continue;
+            }
+            order.push(index);
+            if let Some(kids) = children.get(&node(&entities[index]).0) {
+                stack.extend(kids.iter().rev().filter(|kid| !visited[**kid]));
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<T>> = entities.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|index| slots[index].take())
+        .collect()
 }
 
 fn round3(f: f32) -> f32 {
     (f * 1000.0).round() / 1000.0
 }
 
-fn round_vec3(v: Vec3) -> Vec3 {
+pub(crate) fn round_vec3(v: Vec3) -> Vec3 {
     Vec3::new(round3(v.x), round3(v.y), round3(v.z))
 }
 
-fn round_quat(q: Quat) -> Quat {
+pub(crate) fn round_quat(q: Quat) -> Quat {
     Quat::from_xyzw(round3(q.x), round3(q.y), round3(q.z), round3(q.w))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (name, uuid, parent) stand-ins for saved entities
+    fn sorted_names(entities: Vec<(&'static str, Uuid, Option<Uuid>)>) -> Vec<&'static str> {
+        sort_by_hierarchy(entities, |(_, uuid, parent)| (*uuid, *uuid, *parent))
+            .into_iter()
+            .map(|(name, ..)| name)
+            .collect()
+    }
+
+    #[test]
+    fn parents_come_before_children() {
+        let (parent, child) = (Uuid::from_u128(2), Uuid::from_u128(1));
+        let names = sorted_names(vec![
+            ("child", child, Some(parent)),
+            ("parent", parent, None),
+        ]);
+        assert_eq!(names, ["parent", "child"]);
+    }
+
+    #[test]
+    fn parent_cycles_are_kept() {
+        let (a, b, c) = (Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3));
+        let names = sorted_names(vec![
+            ("c", c, Some(a)),
+            ("b", b, Some(a)),
+            ("a", a, Some(b)),
+        ]);
+        assert_eq!(names, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn duplicate_uuids_in_a_cycle_are_kept_once() {
+        let (x, y) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let names = sorted_names(vec![("a", x, Some(y)), ("b", y, Some(x)), ("c", x, None)]);
+        assert_eq!(names, ["c", "b", "a"]);
+    }
+}
@@ -0,0 +1,245 @@
+// uuid_index.rs
+// Uuid -> Entity lookup for everything with IdentityData, so nothing has to scan the world for
+// one uuid. Observers keep it current the moment IdentityData or SpawnSource is inserted or
+// removed, which also covers command closures that run while a scene loads. In place edits of
+// either component are picked up by sync_uuid_index_system. With duplicate uuids the entity that
+// got its IdentityData last is the one indexed until it goes away, a warning is logged when an
+// entity shadows another one like that. Renaming either one does not change which is indexed
+
+use super::{IdentityData, SpawnSource};
+use bevy::prelude::{Changed, Entity, OnInsert, OnReplace, Or, Query, ResMut, Resource, Trigger};
+use bevy_granite_logging::{
+    config::{LogCategory, LogLevel, LogType},
+    log,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// An entity in the GraniteUuidIndex
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedEntity {
+    pub entity: Entity,
+    pub name: String,
+    /// SpawnSource of the entity, None when it has none
+    pub source: Option<String>,
+}
+
+/// Every entity with IdentityData by uuid
+#[derive(Resource, Debug, Clone, Default)]
+pub struct GraniteUuidIndex {
+    entries: HashMap<Uuid, IndexedEntity>,
+    uuids: HashMap<Entity, Uuid>,
+    // Entities sharing a uuid with the indexed one, oldest first
+    duplicates: HashMap<Uuid, Vec<IndexedEntity>>,
+}
+
+impl GraniteUuidIndex {
+    /// Entity with this uuid
+    pub fn get(&self, uuid: Uuid) -> Option<Entity> {
+        self.entries.get(&uuid).map(|entry| entry.entity)
+    }
+
+    /// Entity, name and source of this uuid
+    pub fn entry(&self, uuid: Uuid) -> Option<&IndexedEntity> {
+        self.entries.get(&uuid)
+    }
+
+    /// Uuid of an entity
+    pub fn uuid_of(&self, entity: Entity) -> Option<Uuid> {
+        self.uuids.get(&entity).copied()
+    }
+
+    /// Name of the entity with this uuid
+    pub fn name(&self, uuid: Uuid) -> Option<&str> {
+        self.entries.get(&uuid).map(|entry| entry.name.as_str())
+    }
+
+    /// Source the entity with this uuid was loaded from or saved to
+    pub fn source(&self, uuid: Uuid) -> Option<&str> {
+        self.entries.get(&uuid)?.source.as_deref()
+    }
+
+    /// Entities with this name, names do not have to be unique
+    pub fn find_by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = (Uuid, Entity)> + 'a {
+        self.iter()
+            .filter(move |(_, entry)| entry.name == name)
+            .map(|(uuid, entry)| (uuid, entry.entity))
+    }
+
+    /// Entities of a source
+    pub fn in_source<'a>(&'a self, source: &'a str) -> impl Iterator<Item = (Uuid, Entity)> + 'a {
+        self.iter()
+            .filter(move |(_, entry)| entry.source.as_deref() == Some(source))
+            .map(|(uuid, entry)| (uuid, entry.entity))
+    }
+
+    /// Uuid of every indexed entity, duplicates included
+    pub fn entity_uuids(&self) -> &HashMap<Entity, Uuid> {
+        &self.uuids
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Uuid, &IndexedEntity)> {
+        self.entries.iter().map(|(uuid, entry)| (*uuid, entry))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Entry of an entity, whether it owns its uuid or is shadowed by a duplicate
+    fn entry_of(&self, entity: Entity) -> Option<&IndexedEntity> {
+        let uuid = self.uuid_of(entity)?;
+        let entries = self.entries.get(&uuid).into_iter();
+        let duplicates = self.duplicates.get(&uuid).into_iter().flatten();
+        entries
+            .chain(duplicates)
+            .find(|entry| entry.entity == entity)
+    }
+
+    fn entry_of_mut(&mut self, entity: Entity) -> Option<&mut IndexedEntity> {
+        let uuid = self.uuid_of(entity)?;
+        let entries = self.entries.get_mut(&uuid).into_iter();
+        let duplicates = self.duplicates.get_mut(&uuid).into_iter().flatten();
+        entries
+            .chain(duplicates)
+            .find(|entry| entry.entity == entity)
+    }
+
+    fn is_current(
+        &self,
+        entity: Entity,
+        identity: &IdentityData,
+        source: Option<&SpawnSource>,
+    ) -> bool {
+        self.uuids.get(&entity) == Some(&identity.uuid)
+            && self.entry_of(entity).is_some_and(|entry| {
+                entry.name == identity.name
+                    && entry.source.as_deref() == source.map(|s| s.str_ref())
+            })
+    }
+
+    // A new uuid indexes the entity again, a new name or source is updated where it is, so a
+    // shadowed duplicate does not take over its uuid by being renamed
+    fn update(&mut self, entity: Entity, identity: &IdentityData, source: Option<&SpawnSource>) {
+        if self.uuid_of(entity) != Some(identity.uuid) {
+            self.insert(entity, identity, source);
+            return;
+        }
+        if let Some(entry) = self.entry_of_mut(entity) {
+            entry.name = identity.name.clone();
+            entry.source = source.map(|s| s.str_ref().to_string());
+        }
+    }
+
+    fn insert(&mut self, entity: Entity, identity: &IdentityData, source: Option<&SpawnSource>) {
+        self.remove(entity);
+        self.uuids.insert(entity, identity.uuid);
+        let entry = IndexedEntity {
+            entity,
+            name: identity.name.clone(),
+            source: source.map(|s| s.str_ref().to_string()),
+        };
+        if let Some(shadowed) = self.entries.insert(identity.uuid, entry) {
+            // Usually a scene loaded twice, or copied from another one, without regenerate_uuids
+            log!(
+                LogType::Game,
+                LogLevel::Warning,
+                LogCategory::Entity,
+                "Duplicate UUID {} on '{}' {:?} from '{}' and '{}' {:?} from '{}'. Parent links and saves may pick the wrong one",
+                identity.uuid,
+                identity.name,
+                entity,
+                source.map(|s| s.str_ref()).unwrap_or("unknown source"),
+                shadowed.name,
+                shadowed.entity,
+                shadowed.source.as_deref().unwrap_or("unknown source")
+            );
+            self.duplicates
+                .entry(identity.uuid)
+                .or_default()
+                .push(shadowed);
+        }
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        let Some(uuid) = self.uuids.remove(&entity) else {
+            return;
+        };
+        if self.get(uuid) == Some(entity) {
+            // The previous owner of the uuid takes over again
+            let previous = self.duplicates.get_mut(&uuid).and_then(Vec::pop);
+            match previous {
+                Some(previous) => self.entries.insert(uuid, previous),
+                None => self.entries.remove(&uuid),
+            };
+        } else if let Some(duplicates) = self.duplicates.get_mut(&uuid) {
+            duplicates.retain(|entry| entry.entity != entity);
+        }
+        if self.duplicates.get(&uuid).is_some_and(Vec::is_empty) {
+            self.duplicates.remove(&uuid);
+        }
+    }
+
+    fn set_source(&mut self, entity: Entity, source: Option<&SpawnSource>) {
+        if let Some(entry) = self.entry_of_mut(entity) {
+            entry.source = source.map(|s| s.str_ref().to_string());
+        }
+    }
+}
+
+pub(crate) fn index_identity_insert_observer(
+    trigger: Trigger<OnInsert, IdentityData>,
+    query: Query<(&IdentityData, Option<&SpawnSource>)>,
+    mut index: ResMut<GraniteUuidIndex>,
+) {
+    if let Ok((identity, source)) = query.get(trigger.target()) {
+        index.insert(trigger.target(), identity, source);
+    }
+}
+
+// Also runs right before a new IdentityData replaces the old one
+pub(crate) fn index_identity_replace_observer(
+    trigger: Trigger<OnReplace, IdentityData>,
+    mut index: ResMut<GraniteUuidIndex>,
+) {
+    index.remove(trigger.target());
+}
+
+pub(crate) fn index_source_insert_observer(
+    trigger: Trigger<OnInsert, SpawnSource>,
+    query: Query<&SpawnSource>,
+    mut index: ResMut<GraniteUuidIndex>,
+) {
+    index.set_source(trigger.target(), query.get(trigger.target()).ok());
+}
+
+pub(crate) fn index_source_replace_observer(
+    trigger: Trigger<OnReplace, SpawnSource>,
+    mut index: ResMut<GraniteUuidIndex>,
+) {
+    index.set_source(trigger.target(), None);
+}
+
+type ChangedIdentityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static IdentityData, Option<&'static SpawnSource>),
+    Or<(Changed<IdentityData>, Changed<SpawnSource>)>,
+>;
+
+/// Pick up IdentityData and SpawnSource that were changed in place, e.g. renamed in the editor
+pub fn sync_uuid_index_system(
+    changed_query: ChangedIdentityQuery,
+    mut index: ResMut<GraniteUuidIndex>,
+) {
+    for (entity, identity, source) in changed_query.iter() {
+        // Only borrow mutably when something changed, so the index is not marked changed every frame
+        if !index.is_current(entity, identity, source) {
+            index.update(entity, identity, source);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt;
+
+/// Everything that can go wrong while reading or writing a Granite scene
+#[derive(Debug)]
+pub enum GraniteSceneError {
+    /// Reading or writing the file itself failed
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    /// The file contents are not valid scene RON
+    Parse {
+        path: String,
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// The scene data could not be turned into RON
+    Serialize { path: String, message: String },
+    /// The scene format version is unsupported or could not be migrated
+    Version {
+        path: String,
+        found: String,
+        message: String,
+    },
+    /// The scene references a class this app does not know
+    UnknownClass {
+        path: String,
+        class: String,
+        line: usize,
+        column: usize,
+    },
+    /// The asset server could not load the scene asset
+    Asset { path: String, message: String },
+}
+
+impl GraniteSceneError {
+    /// Build from a RON parse error, splitting out unknown class variants
+    pub fn from_ron(path: impl Into<String>, error: ron::error::SpannedError) -> Self {
+        let path = path.into();
+        let line = error.position.line;
+        let column = error.position.col;
+        match error.code {
+            ron::Error::NoSuchEnumVariant {
+                found,
+                outer: Some(outer),
+                ..
+            } if outer == "GraniteTypes" => Self::UnknownClass {
+                path,
+                class: found,
+                line,
+                column,
+            },
+            code => Self::Parse {
+                path,
+                message: code.to_string(),
+                line,
+                column,
+            },
+        }
+    }
+
+    /// Path of the scene this error belongs to
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Io { path, .. }
+            | Self::Parse { path, .. }
+            | Self::Serialize { path, .. }
+            | Self::Version { path, .. }
+            | Self::UnknownClass { path, .. }
+            | Self::Asset { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for GraniteSceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "IO error for scene '{}': {}", path, source),
+            Self::Parse {
+                path,
+                message,
+                line,
+                column,
+            } => write!(
+                f,
+                "Failed to parse scene '{}' at {}:{}: {}",
+                path, line, column, message
+            ),
+            Self::Serialize { path, message } => {
+                write!(f, "Failed to serialize scene '{}': {}", path, message)
+            }
+            Self::Version {
+                path,
+                found,
+                message,
+            } => write!(
+                f,
+                "Unsupported scene version '{}' in '{}': {}",
+                found, path, message
+            ),
+            Self::UnknownClass {
+                path,
+                class,
+                line,
+                column,
+            } => write!(
+                f,
+                "Unknown class '{}' in scene '{}' at {}:{}",
+                class, path, line, column
+            ),
+            Self::Asset { path, message } => {
+                write!(f, "Failed to load scene asset '{}': {}", path, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GraniteSceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,11 @@
-use bevy::prelude::Event;
-
+use crate::{
+    entities::{InMemoryScene, SceneLoadOptions, SceneMetadata},
+    error::GraniteSceneError,
+    world::GraniteSceneAsset,
+};
+use bevy::prelude::{Entity, Event, Handle};
+use std::collections::HashMap;
+use uuid::Uuid;
 
 #[derive(Event)]
 pub struct RuntimeDataReadyEvent(pub String);
@@ -7,25 +13,130 @@ pub struct RuntimeDataReadyEvent(pub String);
 #[derive(Event)]
 pub struct CollectRuntimeDataEvent(pub String);
 
+/// Sent once a world is loaded, with the metadata read from its file
+/// e.g. `event.1.custom.get("gravity")` for game specific settings
 #[derive(Event)]
-pub struct WorldLoadSuccessEvent(pub String);
+pub struct WorldLoadSuccessEvent(pub String, pub SceneMetadata);
+
+/// Sent once a loaded scene's entities exist with their classes, components and hierarchy applied
+/// Comes after WorldLoadSuccessEvent, e.g. for gameplay setup right after a level appears
+/// Scenes with prefab instances send it once the members of their source scenes are spawned too
+#[derive(Event, Debug, Clone)]
+pub struct WorldSpawnedEvent {
+    pub source: String,
+    /// Every spawned entity, in the order of the scene file, then the prefab members
+    pub entities: Vec<Entity>,
+    /// Uuid as saved in the scene -> spawned entity, prefab members by their derived uuid
+    /// With SceneLoadOptions::regenerate_uuids the entities carry new uuids in IdentityData
+    pub uuid_to_entity: HashMap<Uuid, Entity>,
+    /// Entities without a parent in the scene
+    pub roots: Vec<Entity>,
+}
 
 #[derive(Event)]
 pub struct WorldSaveSuccessEvent(pub String);
 
+#[derive(Event, Debug)]
+pub struct WorldLoadFailedEvent {
+    pub path: String,
+    pub error: GraniteSceneError,
+}
+
+#[derive(Event, Debug)]
+pub struct WorldSaveFailedEvent {
+    pub path: String,
+    pub error: GraniteSceneError,
+}
+
 // User callable events begin with "Request"
 
 #[derive(Event)]
 pub struct RequestSaveEvent(pub String);
 
+/// Save the given entities and their descendants to a new scene, e.g. to carve a level into pieces
+/// Parents outside the saved entities become roots, placed where they are in the world
+/// With reassign_source the entities belong to the new scene afterwards and are detached from
+/// those parents. Otherwise it is a copy and, like RequestSaveCopyEvent, sends no WorldSaveSuccessEvent
+#[derive(Event)]
+pub struct RequestSaveEntitiesEvent {
+    pub path: String,
+    pub entities: Vec<Entity>,
+    pub reassign_source: bool,
+}
+
+/// Save a loaded source to another file, e.g. an autosave
+/// The source file, its metadata and its entities are left as they are, no WorldSaveSuccessEvent is sent
+#[derive(Event)]
+pub struct RequestSaveCopyEvent {
+    pub source: String,
+    pub path: String,
+}
+
 #[derive(Event)]
 pub struct RequestReloadEvent(pub String);
 
+/// Put a backup of a scene back in place and reload the scene's entities from it
+/// backup 1 is the newest (`name.scene.bak`), see `scene_backups` for what is on disk
+#[derive(Event)]
+pub struct RequestRestoreBackupEvent {
+    pub path: String,
+    pub backup: usize,
+}
+
 #[derive(Event)]
 pub struct RequestLoadEvent(pub String);
 
+/// Load a world additively, optionally under a parent entity and/or offset from its saved position
+/// e.g. to stream level chunks or place the same room scene several times
+#[derive(Event)]
+pub struct RequestLoadWithOptionsEvent {
+    pub path: String,
+    pub options: SceneLoadOptions,
+}
+
+/// Load a scene that is not on disk, tagging its entities with `source`
+/// Sends the same success and failure events as a load from a file. The source is marked in the
+/// SceneMetadataStore until it is saved to a file, so the editor does not save or watch it
+#[derive(Event)]
+pub struct RequestLoadFromMemoryEvent {
+    pub source: String,
+    pub scene: InMemoryScene,
+    pub options: SceneLoadOptions,
+}
+
+/// Export a loaded source as a Bevy DynamicScene (.scn.ron)
+/// Bevy can not serialize asset handles, so meshes and materials are written as DynamicSceneAssetPaths.
+/// Those built in code go into a .gltf next to the scene
+#[derive(Event)]
+pub struct RequestExportDynamicSceneEvent {
+    pub source: String,
+    pub path: String,
+}
+
+/// Import a Bevy DynamicScene (.scn.ron) as Granite entities that belong to source
+/// Lights, cameras and .obj meshes become their class, other components are kept as they are
+#[derive(Event)]
+pub struct RequestImportDynamicSceneEvent {
+    pub path: String,
+    pub source: String,
+}
+
+/// Export a loaded source as glTF, .glb when path ends with it, otherwise .gltf plus a .bin
+/// Granite UUIDs and classes are kept in each node's extras
+#[derive(Event)]
+pub struct RequestExportGltfEvent {
+    pub source: String,
+    pub path: String,
+}
+
+/// Spawn a scene loaded through the asset server, e.g. `asset_server.load("scenes/x.scene")`
+/// Waits for the handle to finish loading, then sends WorldLoadSuccessEvent or WorldLoadFailedEvent
+/// The asset path is used as the source, so handles need one
+#[derive(Event)]
+pub struct RequestSpawnSceneAssetEvent(pub Handle<GraniteSceneAsset>);
+
 #[derive(Event)]
 pub struct RequestDespawnSerializableEntities;
 
 #[derive(Event)]
-pub struct RequestDespawnBySource(pub String);
\ No newline at end of file
+pub struct RequestDespawnBySource(pub String);
@@ -11,6 +11,7 @@ use setup::{gather_registered_types, setup_component_editor};
 // Sub modules
 pub mod assets;
 pub mod entities;
+pub mod error;
 pub mod events;
 pub mod setup;
 pub mod shared;
@@ -31,22 +32,38 @@ pub use assets::{
 };
 pub use bevy_granite_macros::register_editor_components;
 pub use entities::{
-    BridgeTag, Camera3D, ClassCategory, ComponentEditor, DirLight, EditorIgnore,
-    GraniteEditorSerdeEntity, GraniteType, GraniteTypes, HasRuntimeData, IdentityData, MainCamera,
-    MaterialNameSource, NeedsTangents, PointLightData, PromptData, PromptImportSettings, RectBrush,
-    ReflectedComponent, SpawnSource, TransformData, TreeHiddenEntity, UICamera, VolumetricFog, OBJ,
+    BridgeTag, Camera3D, ClassCategory, ComponentEditor, ComponentMigrator, DirLight, EditorIgnore,
+    GraniteComponentHooks, GraniteComponentMigration, GraniteEditorSerdeEntity, GraniteEntityRef,
+    GraniteType, GraniteTypes, GraniteUuidIndex, HasRuntimeData, IdentityData, InMemoryScene,
+    IndexedEntity, MainCamera, MaterialNameSource, NeedsTangents, PointLightData, Prefab,
+    PrefabMember, PreservedSceneData, PromptData, PromptImportSettings, RectBrush,
+    ReflectGraniteComponentHooks, ReflectedComponent, SceneData, SceneLoadOptions, SceneMetadata,
+    SpawnSource, TransformData, TreeHiddenEntity, UICamera, UnresolvedEntityRefs, VolumetricFog,
+    OBJ,
 };
+pub use error::GraniteSceneError;
 pub use events::{
     CollectRuntimeDataEvent, RequestDespawnBySource, RequestDespawnSerializableEntities,
-    RequestLoadEvent, RequestReloadEvent, RequestSaveEvent, RuntimeDataReadyEvent,
-    WorldLoadSuccessEvent, WorldSaveSuccessEvent,
+    RequestExportDynamicSceneEvent, RequestExportGltfEvent, RequestImportDynamicSceneEvent,
+    RequestLoadEvent, RequestLoadFromMemoryEvent, RequestLoadWithOptionsEvent, RequestReloadEvent,
+    RequestRestoreBackupEvent, RequestSaveCopyEvent, RequestSaveEntitiesEvent, RequestSaveEvent,
+    RequestSpawnSceneAssetEvent, RuntimeDataReadyEvent, WorldLoadFailedEvent,
+    WorldLoadSuccessEvent, WorldSaveFailedEvent, WorldSaveSuccessEvent, WorldSpawnedEvent,
 };
 pub use setup::RegisteredTypeNames;
 pub use shared::{
-    absolute_asset_to_rel, get_current_scene_version, get_minimum_scene_version,
-    is_scene_version_compatible, mouse_to_world_delta, CursorWindowPos, IconEntity, IconProxy,
-    IconType, InputTypes, UserInput,
+    absolute_asset_to_rel, backup_path, decode_scene_bytes, get_current_scene_version,
+    get_minimum_scene_version, is_scene_file, is_scene_version_compatible, mouse_to_world_delta,
+    read_scene_file, restore_scene_backup, rewrite_scene_file, scene_backups, write_scene_file,
+    CursorWindowPos, GranitePaths, IconEntity, IconProxy, IconType, InputTypes, SceneCompression,
+    SceneMigration, SceneMigrationFn, SceneMigrations, UserInput, SCENE_FILE_SUFFIXES,
 };
+pub use world::{
+    serialize_to_string, DynamicSceneAssetPaths, GraniteSceneAsset, SceneMetadataStore,
+    SceneSaveSettings,
+};
+// Component migrations work on ron::Value, so games use the same ron as core
+pub use ron;
 
 // Bevy Granite Core plugin
 pub struct BevyGraniteCore {
@@ -75,14 +92,26 @@ impl Plugin for BevyGraniteCore {
             // Events
             //
             .add_event::<RequestLoadEvent>()
+            .add_event::<RequestLoadWithOptionsEvent>()
+            .add_event::<RequestLoadFromMemoryEvent>()
+            .add_event::<RequestSpawnSceneAssetEvent>()
+            .add_event::<RequestExportDynamicSceneEvent>()
+            .add_event::<RequestImportDynamicSceneEvent>()
+            .add_event::<RequestExportGltfEvent>()
             .add_event::<WorldLoadSuccessEvent>()
+            .add_event::<WorldLoadFailedEvent>()
+            .add_event::<WorldSpawnedEvent>()
             .add_event::<RequestDespawnSerializableEntities>()
             .add_event::<RequestDespawnBySource>()
             .add_event::<WorldSaveSuccessEvent>()
+            .add_event::<WorldSaveFailedEvent>()
             .add_event::<RequestSaveEvent>()
+            .add_event::<RequestSaveEntitiesEvent>()
+            .add_event::<RequestSaveCopyEvent>()
             .add_event::<CollectRuntimeDataEvent>()
             .add_event::<RuntimeDataReadyEvent>()
             .add_event::<RequestReloadEvent>()
+            .add_event::<RequestRestoreBackupEvent>()
             //
             // Resources
             //
@@ -0,0 +1,81 @@
+// backup.rs
+// Scene files are never written in place. The new contents go to a temporary sibling first and
+// are renamed over the scene once fully on disk, so a crash or full disk mid-save leaves the old
+// file intact. The replaced version is kept as `name.scene.bak`, older ones as `.bak.2`, `.bak.3`
+
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Path of a scene's backup, 1 is the newest (`name.scene.bak`), then `name.scene.bak.2` and so on
+pub fn backup_path(path: impl AsRef<Path>, index: usize) -> PathBuf {
+    let mut name = OsString::from(path.as_ref().as_os_str());
+    match index {
+        0 | 1 => name.push(".bak"),
+        _ => name.push(format!(".bak.{}", index)),
+    }
+    PathBuf::from(name)
+}
+
+/// Backups of a scene that exist on disk, newest first
+pub fn scene_backups(path: impl AsRef<Path>) -> Vec<PathBuf> {
+    (1..)
+        .map(|index| backup_path(path.as_ref(), index))
+        .take_while(|backup| backup.exists())
+        .collect()
+}
+
+/// Write scene bytes without ever leaving a half written scene behind
+/// With `backups` above 0 the replaced file is kept, rotating out the oldest backup
+pub fn write_scene_file(path: impl AsRef<Path>, bytes: &[u8], backups: usize) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut temp_name = OsString::from(path.as_os_str());
+    temp_name.push(".tmp");
+    let temp_path = PathBuf::from(temp_name);
+
+    let written = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    if backups > 0 && path.exists() {
+        for index in (1..backups).rev() {
+            let older = backup_path(path, index);
+            if older.exists() {
+                fs::rename(&older, backup_path(path, index + 1))?;
+            }
+        }
+        fs::copy(path, backup_path(path, 1))?;
+    }
+
+    fs::rename(&temp_path, path)
+}
+
+/// Rewrite a scene the user did not save, e.g. when it is upgraded in place
+/// Keeps as many backups as the scene already has, at least one, so the original is never lost
+pub fn rewrite_scene_file(path: impl AsRef<Path>, bytes: &[u8]) -> io::Result<()> {
+    let backups = scene_backups(path.as_ref()).len().max(1);
+    write_scene_file(path, bytes, backups)
+}
+
+/// Put a backup back in place of the scene
+/// The current file becomes the newest backup, so a restore can be undone by restoring again
+pub fn restore_scene_backup(
+    path: impl AsRef<Path>,
+    backup: impl AsRef<Path>,
+    backups: usize,
+) -> io::Result<()> {
+    let bytes = fs::read(backup)?;
+    write_scene_file(path, &bytes, backups.max(1))
+}
@@ -0,0 +1,98 @@
+// compression.rs
+// Optional compressed scene storage. Plain RON stays the default so scenes diff well in
+// version control, large scenes can be saved as `.scene.zst` or `.scene.gz` instead.
+// Loading never trusts the extension, the format is detected from the file's magic bytes
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+const ZSTD_LEVEL: i32 = 3;
+
+/// File name endings that are loaded as Granite scenes
+pub const SCENE_FILE_SUFFIXES: [&str; 3] = [".scene", ".scene.zst", ".scene.gz"];
+
+/// How a scene file is stored on disk
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SceneCompression {
+    /// Plain RON text
+    #[default]
+    None,
+    /// Zstandard, `.scene.zst`
+    Zstd,
+    /// Gzip, `.scene.gz`
+    Gzip,
+}
+
+impl SceneCompression {
+    /// Pick the compression from the path's extension, e.g. `level.scene.zst`
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("zst") => Self::Zstd,
+            Some("gz") => Self::Gzip,
+            _ => Self::None,
+        }
+    }
+
+    /// Detect the compression from the first bytes of a file
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&ZSTD_MAGIC) {
+            Self::Zstd
+        } else if bytes.starts_with(&GZIP_MAGIC) {
+            Self::Gzip
+        } else {
+            Self::None
+        }
+    }
+
+    /// Encode serialized scene RON for writing to disk
+    /// Output is deterministic, so unchanged scenes still compare equal to the file on disk
+    pub fn compress(self, contents: &str) -> io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(contents.as_bytes().to_vec()),
+            Self::Zstd => zstd::encode_all(contents.as_bytes(), ZSTD_LEVEL),
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(contents.as_bytes())?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Turn scene file bytes back into RON, decompressing them if they start with zstd or gzip magic
+pub fn decode_scene_bytes(bytes: &[u8]) -> io::Result<String> {
+    let decoded = match SceneCompression::detect(bytes) {
+        SceneCompression::None => bytes.to_vec(),
+        SceneCompression::Zstd => zstd::decode_all(bytes)?,
+        SceneCompression::Gzip => {
+            let mut decoded = Vec::new();
+            GzDecoder::new(bytes).read_to_end(&mut decoded)?;
+            decoded
+        }
+    };
+    String::from_utf8(decoded).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Read a scene file as RON, whatever compression it was saved with
+pub fn read_scene_file(path: impl AsRef<Path>) -> io::Result<String> {
+    decode_scene_bytes(&fs::read(path)?)
+}
+
+/// True for `.scene` files and their compressed variants
+pub fn is_scene_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            SCENE_FILE_SUFFIXES
+                .iter()
+                .any(|suffix| name.ends_with(suffix))
+        })
+}
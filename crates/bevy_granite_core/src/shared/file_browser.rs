@@ -1,3 +1,4 @@
+use super::GranitePaths;
 use bevy_granite_logging::{
     config::{LogCategory, LogLevel, LogType},
     log,
@@ -5,9 +6,8 @@ use bevy_granite_logging::{
 use native_dialog::FileDialog;
 
 pub fn asset_file_browser(path: String, filter: Vec<&str>) -> Option<String> {
-    let current_dir = std::env::current_dir().unwrap();
-    let assets_dir = current_dir.join("assets");
-    let location = assets_dir.join(&path);
+    let paths = GranitePaths::current().clone();
+    let location = paths.to_absolute(&path);
 
     log!(
         LogType::Editor,
@@ -46,7 +46,7 @@ pub fn asset_file_browser(path: String, filter: Vec<&str>) -> Option<String> {
         .show_open_single_file()
         .unwrap()
     {
-        if selected_path.starts_with(&assets_dir) {
+        if paths.contains(&selected_path) {
             Some(selected_path.to_string_lossy().to_string())
         } else {
             log!(
@@ -63,9 +63,8 @@ pub fn asset_file_browser(path: String, filter: Vec<&str>) -> Option<String> {
 }
 
 pub fn asset_file_browser_multiple(path: String, filter: Vec<&str>) -> Option<Vec<String>> {
-    let current_dir = std::env::current_dir().unwrap();
-    let assets_dir = current_dir.join("assets");
-    let location = assets_dir.join(&path);
+    let paths = GranitePaths::current().clone();
+    let location = paths.to_absolute(&path);
 
     log!(
         LogType::Editor,
@@ -111,7 +110,7 @@ pub fn asset_file_browser_multiple(path: String, filter: Vec<&str>) -> Option<Ve
     let mut valid_paths = Vec::new();
 
     for path in selected_paths {
-        if path.starts_with(&assets_dir) {
+        if paths.contains(&path) {
             valid_paths.push(path.to_string_lossy().to_string());
         } else {
             log!(
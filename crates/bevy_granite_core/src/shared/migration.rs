@@ -0,0 +1,397 @@
+use super::version::{get_current_scene_version, get_minimum_scene_version, Version};
+use crate::entities::serialize::scene_pretty_config;
+use bevy::prelude::Resource;
+use bevy_granite_logging::{log, LogCategory, LogLevel, LogType};
+use ron::value::RawValue;
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+// migration.rs
+// Scene files carry the format version they were written with (metadata.format_version).
+// When versions.toml bumps current_version, a step from the previous version must be registered here
+// so older scenes are rewritten step by step until they reach the current format before being parsed.
+
+/// A single upgrade step. Takes the raw RON of a scene at the step's `from` version
+/// and returns the raw RON rewritten for its `to` version
+pub type SceneMigrationFn = fn(&str) -> Result<String, String>;
+
+#[derive(Clone, Debug)]
+pub struct SceneMigration {
+    pub from: String,
+    pub to: String,
+    pub migrate: SceneMigrationFn,
+}
+
+/// Output of running a scene through the migration pipeline
+#[derive(Debug, Clone)]
+pub struct MigratedScene {
+    /// Raw RON after all steps were applied
+    pub contents: String,
+    /// Version the file was written with
+    pub original_version: String,
+    /// Steps that ran, formatted as "from -> to"
+    pub applied: Vec<String>,
+}
+
+impl MigratedScene {
+    pub fn was_migrated(&self) -> bool {
+        !self.applied.is_empty()
+    }
+}
+
+/// Registry of scene format migration steps keyed by the version they upgrade from
+#[derive(Resource, Clone)]
+pub struct SceneMigrations {
+    steps: BTreeMap<Version, SceneMigration>,
+    /// Write the migrated scene back over the original file after a successful load
+    /// The original is kept as a backup
+    pub upgrade_in_place: bool,
+}
+
+impl Default for SceneMigrations {
+    fn default() -> Self {
+        // Built-in steps for our own format bumps get registered here
+        let mut migrations = Self {
+            steps: BTreeMap::new(),
+            upgrade_in_place: false,
+        };
+        migrations.register("0.1.4", "0.1.5", migrate_stringified_components);
+        migrations
+    }
+}
+
+// Only the part of the scene we need to know which steps to run
+#[derive(Deserialize)]
+struct SceneHeader {
+    metadata: SceneHeaderMetadata,
+}
+
+#[derive(Deserialize)]
+struct SceneHeaderMetadata {
+    format_version: String,
+}
+
+// Scene with only the metadata fields split out, used to bump its version
+#[derive(Serialize, Deserialize)]
+struct RawScene<M> {
+    metadata: M,
+    entities: Box<RawValue>,
+}
+
+// Metadata fields in file order with their values as raw RON, so fields this build
+// does not know are written back as they were
+struct RawMetadataFields(Vec<(String, Box<RawValue>)>);
+
+impl<'de> Deserialize<'de> for RawMetadataFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = RawMetadataFields;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("scene metadata")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut fields = Vec::new();
+                while let Some(name) = map.next_key::<String>()? {
+                    fields.push((name, map.next_value::<Box<RawValue>>()?));
+                }
+                Ok(RawMetadataFields(fields))
+            }
+        }
+
+        deserializer.deserialize_struct("SceneMetadata", &[], FieldsVisitor)
+    }
+}
+
+impl SceneMigrations {
+    /// Register a step that upgrades scenes written at `from` to `to`
+    /// Replaces any step already registered for `from`
+    pub fn register(&mut self, from: &str, to: &str, migrate: SceneMigrationFn) -> &mut Self {
+        match (Version::parse(from), Version::parse(to)) {
+            (Ok(from_version), Ok(to_version)) if from_version < to_version => {
+                self.steps.insert(
+                    from_version,
+                    SceneMigration {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        migrate,
+                    },
+                );
+            }
+            _ => {
+                log!(
+                    LogType::Game,
+                    LogLevel::Error,
+                    LogCategory::System,
+                    "Ignoring scene migration '{}' -> '{}': versions must be valid and increasing",
+                    from,
+                    to
+                );
+            }
+        }
+        self
+    }
+
+    /// All registered steps in version order
+    pub fn steps(&self) -> impl Iterator<Item = &SceneMigration> {
+        self.steps.values()
+    }
+
+    /// Apply registered steps in order until the scene reaches the current format version
+    /// Scenes that are already current (or newer), or that have no metadata, are returned untouched
+    pub fn migrate(&self, contents: &str) -> Result<MigratedScene, String> {
+        let Ok(original_version) = read_scene_format_version(contents) else {
+            return Ok(MigratedScene {
+                contents: contents.to_string(),
+                original_version: String::new(),
+                applied: Vec::new(),
+            });
+        };
+
+        let current = Version::parse(&get_current_scene_version())?;
+        let minimum = Version::parse(&get_minimum_scene_version())?;
+        let mut version = Version::parse(&original_version)?;
+        let mut contents = contents.to_string();
+        let mut applied = Vec::new();
+
+        while version < current {
+            let Some(step) = self.steps.get(&version) else {
+                if version >= minimum {
+                    // Old but still readable without rewriting
+                    break;
+                }
+                return Err(format!(
+                    "No migration registered from scene version '{}' (current: '{}', min supported: '{}')",
+                    version, current, minimum
+                ));
+            };
+
+            contents = (step.migrate)(&contents)
+                .map_err(|e| format!("Migration '{}' -> '{}' failed: {}", step.from, step.to, e))?;
+            contents = set_scene_format_version(&contents, &step.to)
+                .map_err(|e| format!("Migration '{}' -> '{}' failed: {}", step.from, step.to, e))?;
+
+            log!(
+                LogType::Game,
+                LogLevel::Info,
+                LogCategory::System,
+                "Migrated scene data '{}' -> '{}'",
+                step.from,
+                step.to
+            );
+
+            applied.push(format!("{} -> {}", step.from, step.to));
+            version = Version::parse(&step.to)?;
+        }
+
+        Ok(MigratedScene {
+            contents,
+            original_version,
+            applied,
+        })
+    }
+}
+
+/// Read metadata.format_version without parsing the rest of the scene
+pub fn read_scene_format_version(contents: &str) -> Result<String, String> {
+    ron::from_str::<SceneHeader>(contents)
+        .map(|header| header.metadata.format_version)
+        .map_err(|e| e.to_string())
+}
+
+/// Rewrite the metadata.format_version of a raw scene
+/// Entities and every other metadata field are carried over untouched
+pub fn set_scene_format_version(contents: &str, version: &str) -> Result<String, String> {
+    let scene: RawScene<RawMetadataFields> = ron::from_str(contents).map_err(|e| e.to_string())?;
+    let version = RawValue::from_rust(&version).map_err(|e| e.to_string())?;
+
+    let mut fields = scene.metadata.0;
+    match fields.iter_mut().find(|(name, _)| name == "format_version") {
+        Some((_, value)) => *value = version,
+        None => fields.insert(0, ("format_version".to_string(), version)),
+    }
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value.trim().get_ron()))
+        .collect();
+    let metadata = RawValue::from_boxed_ron(format!("({})", fields.join(", ")).into_boxed_str())
+        .map_err(|e| e.to_string())?;
+
+    let scene = RawScene {
+        metadata,
+        entities: scene.entities.trim_boxed(),
+    };
+    ron::ser::to_string_pretty(&scene, scene_pretty_config()).map_err(|e| e.to_string())
+}
+
+//
+// Built-in steps
+//
+
+// Entity layout that only cares about how components are stored.
+// Everything else is carried over untouched as raw RON
+#[derive(Serialize, Deserialize)]
+struct RawSceneEntities<C> {
+    metadata: Box<RawValue>,
+    entities: Vec<RawSceneEntity<C>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawSceneEntity<C> {
+    identity: Box<RawValue>,
+    transform: Box<RawValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<Box<RawValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<BTreeMap<String, C>>,
+}
+
+/// 0.1.4 -> 0.1.5
+/// Components used to be stringified `{"type::path": (..)}` blobs, they are now nested RON values
+fn migrate_stringified_components(contents: &str) -> Result<String, String> {
+    let legacy: RawSceneEntities<String> = ron::from_str(contents).map_err(|e| e.to_string())?;
+
+    let mut entities = Vec::with_capacity(legacy.entities.len());
+    for entity in legacy.entities {
+        let components = match entity.components {
+            Some(components) => {
+                let mut nested = BTreeMap::new();
+                for (type_path, blob) in components {
+                    let mut wrapper: HashMap<String, Box<RawValue>> = ron::from_str(&blob)
+                        .map_err(|e| format!("Component '{}': {}", type_path, e))?;
+                    let value = wrapper.remove(&type_path).ok_or_else(|| {
+                        format!("Component '{}' is missing its type path key", type_path)
+                    })?;
+                    nested.insert(type_path, value.trim_boxed());
+                }
+                Some(nested)
+            }
+            None => None,
+        };
+
+        entities.push(RawSceneEntity {
+            identity: entity.identity.trim_boxed(),
+            transform: entity.transform.trim_boxed(),
+            parent: entity.parent.map(RawValue::trim_boxed),
+            components,
+        });
+    }
+
+    let upgraded = RawSceneEntities {
+        metadata: legacy.metadata.trim_boxed(),
+        entities,
+    };
+    ron::ser::to_string_pretty(&upgraded, scene_pretty_config()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene(version: &str, components: &str) -> String {
+        format!(
+            r#"(
+    metadata: (format_version: "{version}", entity_count: 1),
+    entities: [
+        (
+            identity: (uuid: "6c0f6a8e-2f44-4d2a-9d6b-0c6a1f2b3c4d", name: "Crate", class: Empty(())),
+            transform: (position: (0.0, 0.0, 0.0), rotation: (0.0, 0.0, 0.0, 1.0), scale: (1.0, 1.0, 1.0)),
+            {components}
+        ),
+    ],
+)"#
+        )
+    }
+
+    #[test]
+    fn current_scenes_are_untouched() {
+        let contents = scene(&get_current_scene_version(), "");
+        let migrated = SceneMigrations::default().migrate(&contents).unwrap();
+        assert!(!migrated.was_migrated());
+        assert_eq!(migrated.contents, contents);
+    }
+
+    #[test]
+    fn stringified_components_are_nested() {
+        let contents = scene(
+            "0.1.4",
+            r#"components: Some({"game::Health": "{\"game::Health\": (value: 3)}"}),"#,
+        );
+        let migrated = SceneMigrations::default().migrate(&contents).unwrap();
+        assert_eq!(migrated.original_version, "0.1.4");
+        assert_eq!(migrated.applied, ["0.1.4 -> 0.1.5"]);
+        assert_eq!(
+            read_scene_format_version(&migrated.contents).unwrap(),
+            "0.1.5"
+        );
+
+        let upgraded: RawSceneEntities<Box<RawValue>> = ron::from_str(&migrated.contents).unwrap();
+        let components = upgraded.entities[0].components.as_ref().unwrap();
+        assert_eq!(components["game::Health"].trim().get_ron(), "(value: 3)");
+    }
+
+    #[test]
+    fn registered_steps_run_in_order() {
+        let mut migrations = SceneMigrations::default();
+        migrations.register("0.1.3", "0.1.4", |contents| {
+            Ok(contents.replace("\"Crate\"", "\"Box\""))
+        });
+        let migrated = migrations.migrate(&scene("0.1.3", "")).unwrap();
+        assert_eq!(migrated.applied, ["0.1.3 -> 0.1.4", "0.1.4 -> 0.1.5"]);
+        assert!(migrated.contents.contains("\"Box\""));
+    }
+
+    #[test]
+    fn failing_steps_name_the_step() {
+        let mut migrations = SceneMigrations::default();
+        migrations.register("0.1.3", "0.1.4", |_| Err("bad data".to_string()));
+        let error = migrations.migrate(&scene("0.1.3", "")).unwrap_err();
+        assert_eq!(error, "Migration '0.1.3' -> '0.1.4' failed: bad data");
+    }
+
+    #[test]
+    fn unknown_metadata_fields_are_kept() {
+        let contents = scene("0.1.4", "").replace(
+            "entity_count: 1)",
+            "entity_count: 1, studio_notes: Some(\"keep me\"), custom: {\"gravity\": \"9.8\"})",
+        );
+        let migrated = SceneMigrations::default().migrate(&contents).unwrap();
+        assert_eq!(
+            read_scene_format_version(&migrated.contents).unwrap(),
+            "0.1.5"
+        );
+
+        let upgraded: RawScene<RawMetadataFields> = ron::from_str(&migrated.contents).unwrap();
+        let fields: Vec<(&str, &str)> = upgraded
+            .metadata
+            .0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.trim().get_ron()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("format_version", "\"0.1.5\""),
+                ("entity_count", "1"),
+                ("studio_notes", "Some(\"keep me\")"),
+                ("custom", "{\"gravity\": \"9.8\"}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn too_old_scenes_without_a_step_fail() {
+        assert!(SceneMigrations::default()
+            .migrate(&scene("0.1.0", ""))
+            .is_err());
+    }
+}
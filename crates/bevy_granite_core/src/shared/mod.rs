@@ -1,13 +1,28 @@
+pub mod backup;
+pub mod compression;
 pub mod icon;
+pub mod migration;
+pub mod paths;
 pub mod plugin;
 pub mod user_input;
 pub mod version;
 pub mod file_browser;
 pub mod file;
 
+pub use backup::{
+    backup_path, restore_scene_backup, rewrite_scene_file, scene_backups, write_scene_file,
+};
+pub use compression::{
+    decode_scene_bytes, is_scene_file, read_scene_file, SceneCompression, SCENE_FILE_SUFFIXES,
+};
 pub use file::*;
 pub use file_browser::{asset_file_browser, asset_file_browser_multiple};
 pub use icon::{IconEntity, IconProxy, IconType};
+pub use migration::{
+    read_scene_format_version, set_scene_format_version, MigratedScene, SceneMigration,
+    SceneMigrationFn, SceneMigrations,
+};
+pub use paths::{sync_granite_paths_system, GranitePaths};
 pub use plugin::SharedPlugin;
 pub use user_input::{
     capture_input_events, mouse_to_world_delta, update_mouse_pos, CursorWindowPos, InputTypes,
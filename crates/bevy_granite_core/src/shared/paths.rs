@@ -0,0 +1,116 @@
+// paths.rs
+// Every conversion between asset paths and disk paths goes through GranitePaths. The resource is
+// what apps configure, a copy is kept in a global so the free path functions used all over (and
+// off the main thread) see the same roots. Named sources are written like Bevy asset paths,
+// `mods://levels/one.scene`, and only need their folder registered here
+
+use bevy::{
+    asset::io::file::FileAssetReader,
+    prelude::{DetectChanges, Res, Resource},
+};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{LazyLock, RwLock, RwLockReadGuard},
+};
+
+static CURRENT_PATHS: LazyLock<RwLock<GranitePaths>> =
+    LazyLock::new(|| RwLock::new(GranitePaths::default()));
+
+/// Folders asset paths resolve to
+/// Inserted from `AssetPlugin::file_path` unless the app inserts its own first
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct GranitePaths {
+    /// Folder of the default asset source
+    pub root: PathBuf,
+    /// Named asset sources, e.g. `mods` for `mods://levels/one.scene`
+    pub sources: BTreeMap<String, PathBuf>,
+}
+
+impl Default for GranitePaths {
+    fn default() -> Self {
+        Self::from_file_path("assets")
+    }
+}
+
+impl GranitePaths {
+    /// Root relative to Bevy's base path, like `AssetPlugin::file_path`
+    pub fn from_file_path(file_path: &str) -> Self {
+        Self {
+            root: FileAssetReader::get_base_path().join(file_path),
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// Register the folder of a named asset source
+    pub fn with_source(mut self, name: impl Into<String>, folder: impl Into<PathBuf>) -> Self {
+        self.sources.insert(name.into(), folder.into());
+        self
+    }
+
+    /// Paths the free path functions currently use
+    pub fn current() -> RwLockReadGuard<'static, GranitePaths> {
+        CURRENT_PATHS
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Disk path of an asset path, absolute paths are kept as they are
+    /// Paths of a source that is not registered are kept as they are too
+    pub fn to_absolute(&self, asset_path: &str) -> PathBuf {
+        match asset_path.split_once("://") {
+            Some((source, path)) => match self.sources.get(source) {
+                Some(folder) => folder.join(path),
+                None => PathBuf::from(asset_path),
+            },
+            None => self.root.join(asset_path),
+        }
+    }
+
+    /// Asset path of a disk path inside one of the roots, `source://path` for named sources
+    /// Other paths are kept as they are
+    pub fn to_relative(&self, path: &str) -> String {
+        let disk_path = Path::new(path);
+        if !disk_path.is_absolute() {
+            return path.to_string();
+        }
+
+        // Longest folder first, so a source nested inside the root wins
+        let mut roots: Vec<(Option<&str>, &Path)> = self
+            .sources
+            .iter()
+            .map(|(name, folder)| (Some(name.as_str()), folder.as_path()))
+            .chain(std::iter::once((None, self.root.as_path())))
+            .collect();
+        roots.sort_by_key(|(_, folder)| std::cmp::Reverse(folder.as_os_str().len()));
+
+        for (source, folder) in roots {
+            if let Ok(rel_path) = disk_path.strip_prefix(folder) {
+                let rel_path = rel_path.to_string_lossy().replace("\\", "/");
+                return match source {
+                    Some(source) => format!("{}://{}", source, rel_path),
+                    None => rel_path,
+                };
+            }
+        }
+        path.to_string()
+    }
+
+    /// Whether a disk path is inside the root or a named source
+    pub fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.root) || self.sources.values().any(|folder| path.starts_with(folder))
+    }
+}
+
+/// Keep the global copy used by the free path functions in step with the resource
+pub fn sync_granite_paths_system(paths: Res<GranitePaths>) {
+    if paths.is_changed() {
+        set_current_paths(paths.clone());
+    }
+}
+
+pub(crate) fn set_current_paths(paths: GranitePaths) {
+    *CURRENT_PATHS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = paths;
+}
@@ -1,10 +1,25 @@
-use super::{capture_input_events, update_mouse_pos, CursorWindowPos, UserInput};
-use bevy::app::{Plugin, PreUpdate};
+use super::{
+    capture_input_events, paths::set_current_paths, sync_granite_paths_system, update_mouse_pos,
+    CursorWindowPos, GranitePaths, UserInput,
+};
+use bevy::app::{First, Plugin, PreStartup, PreUpdate};
+use bevy::asset::AssetPlugin;
 use bevy::prelude::{App, Update};
 
 pub struct SharedPlugin;
 impl Plugin for SharedPlugin {
     fn build(&self, app: &mut App) {
+        // Same root as the asset server, unless the app brought its own paths
+        if !app.world().contains_resource::<GranitePaths>() {
+            let paths = app
+                .get_added_plugins::<AssetPlugin>()
+                .first()
+                .map(|asset_plugin| GranitePaths::from_file_path(&asset_plugin.file_path))
+                .unwrap_or_default();
+            app.insert_resource(paths);
+        }
+        set_current_paths(app.world().resource::<GranitePaths>().clone());
+
         app
             //
             // Resources
@@ -14,6 +29,8 @@ impl Plugin for SharedPlugin {
             //
             // Schedule systems
             //
+            .add_systems(PreStartup, sync_granite_paths_system)
+            .add_systems(First, sync_granite_paths_system)
             .add_systems(PreUpdate, capture_input_events)
             .add_systems(Update, update_mouse_pos);
     }
@@ -16,7 +16,7 @@ struct SceneFormatConfig {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Version {
+pub(crate) struct Version {
     major: u32,
     minor: u32,
     patch: u32,
@@ -25,7 +25,7 @@ struct Version {
 }
 
 impl Version {
-    fn parse(version_str: &str) -> Result<Self, String> {
+    pub(crate) fn parse(version_str: &str) -> Result<Self, String> {
         let parts: Vec<&str> = version_str.split('+').collect();
         let (version_part, build) = match parts.len() {
             1 => (parts[0], None),
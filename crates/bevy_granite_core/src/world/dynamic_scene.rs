@@ -0,0 +1,624 @@
+use crate::{
+    assets::{AvailableEditableMaterials, EditableMaterial, MaterialData, StandardMaterialDef},
+    entities::{
+        component_migration::current_component_versions, serialize::scene_pretty_config,
+        spawn_scene_entities, with_entity_uuids, Camera3D, DirLight, EntitySaveReadyData,
+        GraniteType, GraniteTypes, IdentityData, PointLightData, SceneLoadOptions, SceneMetadata,
+        SceneSpawnAssets, SpawnSource, TransformData, OBJ,
+    },
+    error::GraniteSceneError,
+    events::{
+        RequestExportDynamicSceneEvent, RequestImportDynamicSceneEvent, WorldLoadFailedEvent,
+        WorldLoadSuccessEvent,
+    },
+    shared::rel_asset_to_absolute,
+    world::{gltf_export::GltfBuilder, GltfExport, SceneMetadataStore},
+};
+use bevy::{
+    color::{ColorToComponents, LinearRgba},
+    core_pipeline::core_3d::Camera3d,
+    pbr::{DirectionalLight, MeshMaterial3d, PointLight, StandardMaterial, VolumetricLight},
+    prelude::*,
+    reflect::{serde::TypedReflectSerializer, FromReflect, TypeRegistry},
+    render::{
+        camera::Camera,
+        mesh::Mesh3d,
+        view::{InheritedVisibility, ViewVisibility},
+    },
+    scene::{serde::SceneDeserializer, DynamicScene, DynamicSceneBuilder, SceneFilter},
+    transform::components::TransformTreeChanged,
+};
+use bevy_granite_logging::{
+    config::{LogCategory, LogLevel, LogType},
+    log,
+};
+use ron::value::RawValue;
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
+use uuid::Uuid;
+
+// dynamic_scene.rs
+// Conversion between Granite sources and Bevy's own DynamicScene (.scn.ron).
+// Export takes the live entities of a source, so every class is already expanded into the Bevy
+// components it spawned (PointLight, Camera...) and Granite's own types are left out. Asset
+// handles cannot be serialized, so Mesh3d and MeshMaterial3d are written as DynamicSceneAssetPaths
+// from bevy_granite_scene_assets, which games can load without the rest of Granite.
+// Import is best effort: lights, cameras and .obj meshes become their class, everything else is
+// carried over as components.
+
+pub use bevy_granite_scene_assets::DynamicSceneAssetPaths;
+
+// Bevy components that the importer turns into identity, transform and parent instead of components
+// or that Bevy computes again on spawn
+fn consumed_by_import(type_id: TypeId) -> bool {
+    [
+        TypeId::of::<Name>(),
+        TypeId::of::<Transform>(),
+        TypeId::of::<GlobalTransform>(),
+        TypeId::of::<TransformTreeChanged>(),
+        TypeId::of::<ChildOf>(),
+        TypeId::of::<Children>(),
+        TypeId::of::<InheritedVisibility>(),
+        TypeId::of::<ViewVisibility>(),
+    ]
+    .contains(&type_id)
+}
+
+/// A source ready to be written as a .scn.ron, with the glTF that holds its meshes and materials
+/// built in code when it has any
+pub struct DynamicSceneExport {
+    pub scene: DynamicScene,
+    pub assets: Option<GltfExport>,
+}
+
+/// Build a DynamicScene from every entity of a loaded source
+/// Granite types (identity, spawn source, markers...) are denied, apart from DynamicSceneAssetPaths.
+/// path is where the scene will be written, relative to the assets folder. Meshes and materials
+/// without an asset path of their own (rect brushes, Granite materials...) go into a glTF next to
+/// it, see scene_assets_path. Fails when such a mesh is not loaded or not a triangle list
+pub fn dynamic_scene_from_source(
+    world: &World,
+    source: &str,
+    path: &str,
+) -> Result<DynamicSceneExport, String> {
+    let mut entities = Vec::new();
+    for entity_ref in world.iter_entities() {
+        if entity_ref
+            .get::<SpawnSource>()
+            .is_some_and(|spawn_source| spawn_source.str_ref() == source)
+        {
+            entities.push(entity_ref.id());
+        }
+    }
+
+    // Children also lists editor only entities like icons, ChildOf alone rebuilds the hierarchy
+    let mut filter = SceneFilter::allow_all()
+        .deny::<Children>()
+        .deny::<Mesh3d>()
+        .deny::<MeshMaterial3d<StandardMaterial>>();
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    for registration in type_registry.iter() {
+        if registration
+            .type_info()
+            .type_path()
+            .starts_with("bevy_granite")
+        {
+            filter = filter.deny_by_id(registration.type_id());
+        }
+    }
+
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .with_component_filter(filter)
+        .extract_entities(entities.into_iter())
+        .build();
+
+    let assets_path = scene_assets_path(path);
+    let mut assets = GltfBuilder::new(Path::new(&rel_asset_to_absolute(&assets_path).to_string()));
+    let mut unresolved = Vec::new();
+    for dynamic_entity in scene.entities.iter_mut() {
+        let entity_ref = world.entity(dynamic_entity.entity);
+        let mesh = entity_ref.get::<Mesh3d>();
+        let material = entity_ref.get::<MeshMaterial3d<StandardMaterial>>();
+        if mesh.is_none() && material.is_none() {
+            continue;
+        }
+
+        let name = entity_ref
+            .get::<IdentityData>()
+            .map(|identity| identity.name.clone())
+            .unwrap_or_else(|| dynamic_entity.entity.to_string());
+        let mesh = match mesh {
+            Some(mesh) => match mesh_path(world, &mut assets, &assets_path, &mesh.0, &name) {
+                Some(mesh) => Some(mesh),
+                None => {
+                    unresolved.push(name);
+                    continue;
+                }
+            },
+            None => None,
+        };
+        let material = material.and_then(|material| {
+            material_path(world, &mut assets, &assets_path, &material.0, &name)
+        });
+
+        dynamic_entity
+            .components
+            .push(Box::new(DynamicSceneAssetPaths { mesh, material }));
+    }
+
+    if !unresolved.is_empty() {
+        return Err(format!(
+            "meshes that are not loaded or not a triangle list on: {}",
+            unresolved.join(", ")
+        ));
+    }
+
+    let assets = assets.finish(Vec::new(), Vec::new());
+    let has_assets = ["meshes", "materials"]
+        .iter()
+        .any(|key| assets.json.get(*key).is_some());
+    Ok(DynamicSceneExport {
+        scene,
+        assets: has_assets.then_some(assets),
+    })
+}
+
+/// Where the meshes and materials built in code of a scene exported to path are written,
+/// e.g. levels/arena.scn.gltf for levels/arena.scn.ron
+pub fn scene_assets_path(path: &str) -> String {
+    Path::new(path)
+        .with_extension("gltf")
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn asset_path<A: Asset>(handle: &Handle<A>) -> Option<String> {
+    handle.path().map(|path| path.to_string())
+}
+
+// Meshes built in code are added to the scene's glTF and referenced by Bevy's glTF label
+fn mesh_path(
+    world: &World,
+    assets: &mut GltfBuilder,
+    assets_path: &str,
+    handle: &Handle<Mesh>,
+    name: &str,
+) -> Option<String> {
+    if let Some(path) = asset_path(handle) {
+        return Some(path);
+    }
+
+    let meshes = world.resource::<Assets<Mesh>>();
+    let index = assets.add_mesh(meshes, handle.id(), name, None)?;
+    Some(format!("{assets_path}#Mesh{index}/Primitive0"))
+}
+
+// Granite materials are added in code from .mat files that only Granite reads, so those and any
+// other material without a path are added to the scene's glTF
+fn material_path(
+    world: &World,
+    assets: &mut GltfBuilder,
+    assets_path: &str,
+    handle: &Handle<StandardMaterial>,
+    name: &str,
+) -> Option<String> {
+    if let Some(path) = asset_path(handle) {
+        return Some(path);
+    }
+
+    let editable = world
+        .get_resource::<AvailableEditableMaterials>()
+        .and_then(|available| available.materials.as_ref())
+        .and_then(|materials| {
+            materials
+                .iter()
+                .find(|material| material.handle.as_ref() == Some(handle))
+        });
+    let index = match editable {
+        Some(editable) => assets.add_material(editable),
+        None => {
+            let material = world.resource::<Assets<StandardMaterial>>().get(handle)?;
+            assets.add_material(&EditableMaterial {
+                path: handle.id().to_string(),
+                friendly_name: name.to_string(),
+                handle: None,
+                def: Some(material_def(material)),
+                ..default()
+            })
+        }
+    };
+    Some(format!("{assets_path}#Material{index}"))
+}
+
+// The parts of a material built in code that the glTF export writes
+fn material_def(material: &StandardMaterial) -> StandardMaterialDef {
+    let srgba = material.base_color.to_srgba();
+    let emissive = Color::from(material.emissive).to_srgba();
+    let texture = |texture: &Option<Handle<Image>>| texture.as_ref().and_then(asset_path);
+
+    StandardMaterialDef {
+        base_color: Some((srgba.red, srgba.green, srgba.blue, srgba.alpha)),
+        roughness: Some(material.perceptual_roughness),
+        metalness: Some(material.metallic),
+        emissive: Some((emissive.red, emissive.green, emissive.blue)),
+        double_sided: Some(material.double_sided),
+        unlit: Some(material.unlit),
+        alpha_mode: match material.alpha_mode {
+            AlphaMode::Blend => Some("Blend".to_string()),
+            AlphaMode::Mask(_) => Some("Mask".to_string()),
+            _ => None,
+        },
+        base_color_texture: texture(&material.base_color_texture),
+        emissive_texture: texture(&material.emissive_texture),
+        normal_map_texture: texture(&material.normal_map_texture),
+        occlusion_map: texture(&material.occlusion_texture),
+        ..default()
+    }
+}
+
+/// Turn a DynamicScene into Granite save data with freshly generated UUIDs
+/// Other components are kept as RON, anything that cannot be serialized is dropped with a warning.
+/// Spawning keeps the ones saving would skip in PreservedSceneData, so they survive a save
+pub fn entities_from_dynamic_scene(
+    scene: &DynamicScene,
+    type_registry: &TypeRegistry,
+) -> Vec<EntitySaveReadyData> {
+    let uuids: HashMap<Entity, Uuid> = scene
+        .entities
+        .iter()
+        .map(|dynamic_entity| (dynamic_entity.entity, Uuid::new_v4()))
+        .collect();
+
+    scene
+        .entities
+        .iter()
+        .map(|dynamic_entity| {
+            let components: Vec<&dyn PartialReflect> = dynamic_entity
+                .components
+                .iter()
+                .map(|component| component.as_ref())
+                .collect();
+            let find = |type_id: TypeId| {
+                components
+                    .iter()
+                    .copied()
+                    .find(|component| represents(*component, type_id))
+            };
+
+            let (class, class_components) = class_from_components(&find);
+            let class = class.unwrap_or(GraniteTypes::Empty(Default::default()));
+            let name = find(TypeId::of::<Name>())
+                .and_then(Name::from_reflect)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("{} {}", class.type_name(), dynamic_entity.entity));
+            let transform = find(TypeId::of::<Transform>())
+                .and_then(Transform::from_reflect)
+                .unwrap_or_default();
+            let parent = find(TypeId::of::<ChildOf>())
+                .and_then(ChildOf::from_reflect)
+                .and_then(|child_of| uuids.get(&child_of.parent()).copied());
+
+            let mut saved_components = BTreeMap::new();
+            for component in components.iter().copied() {
+                let Some(type_info) = component.get_represented_type_info() else {
+                    continue;
+                };
+                let type_id = type_info.type_id();
+                if consumed_by_import(type_id) || class_components.contains(&type_id) {
+                    continue;
+                }
+
+                let serializer = TypedReflectSerializer::new(component, type_registry);
+                match ron::ser::to_string_pretty(&serializer, scene_pretty_config())
+                    .map_err(|e| e.to_string())
+                    .and_then(|serialized| {
+                        RawValue::from_boxed_ron(serialized.into_boxed_str())
+                            .map_err(|e| e.to_string())
+                    }) {
+                    Ok(raw) => {
+                        saved_components.insert(type_info.type_path().to_string(), raw);
+                    }
+                    Err(e) => {
+                        log!(
+                            LogType::Game,
+                            LogLevel::Warning,
+                            LogCategory::System,
+                            "Dropped component {} on imported entity '{}': {}",
+                            type_info.type_path(),
+                            name,
+                            e
+                        );
+                    }
+                }
+            }
+
+            EntitySaveReadyData {
+                identity: IdentityData {
+                    uuid: uuids[&dynamic_entity.entity],
+                    name,
+                    class,
+                },
+                transform: TransformData {
+                    position: transform.translation,
+                    rotation: transform.rotation,
+                    scale: transform.scale,
+                },
+                parent,
+                components: (!saved_components.is_empty()).then_some(saved_components),
+                preserved_class: None,
+            }
+        })
+        .collect()
+}
+
+fn represents(component: &dyn PartialReflect, type_id: TypeId) -> bool {
+    component
+        .get_represented_type_info()
+        .is_some_and(|type_info| type_info.type_id() == type_id)
+}
+
+// Pick the class whose spawn would produce these components
+// Returns the class and the components it covers, so they are not saved twice
+fn class_from_components<'a>(
+    find: &impl Fn(TypeId) -> Option<&'a dyn PartialReflect>,
+) -> (Option<GraniteTypes>, Vec<TypeId>) {
+    let color = |color: Color| {
+        let [r, g, b] = LinearRgba::from(color).to_f32_array_no_alpha();
+        (r, g, b)
+    };
+
+    if let Some(light) = find(TypeId::of::<PointLight>()).and_then(PointLight::from_reflect) {
+        let class = PointLightData {
+            intensity: light.intensity,
+            range: light.range,
+            shadows_enabled: light.shadows_enabled,
+            color: color(light.color),
+        };
+        return (
+            Some(GraniteTypes::PointLightData(class)),
+            vec![TypeId::of::<PointLight>()],
+        );
+    }
+
+    if let Some(light) =
+        find(TypeId::of::<DirectionalLight>()).and_then(DirectionalLight::from_reflect)
+    {
+        let class = DirLight {
+            color: color(light.color),
+            illuminance: light.illuminance,
+            shadows_enabled: light.shadows_enabled,
+            volumetric: find(TypeId::of::<VolumetricLight>()).is_some(),
+        };
+        return (
+            Some(GraniteTypes::DirLight(class)),
+            vec![
+                TypeId::of::<DirectionalLight>(),
+                TypeId::of::<VolumetricLight>(),
+            ],
+        );
+    }
+
+    if find(TypeId::of::<Camera3d>()).is_some() {
+        let class = Camera3D {
+            is_active: find(TypeId::of::<Camera>())
+                .and_then(Camera::from_reflect)
+                .is_none_or(|camera| camera.is_active),
+            ..Default::default()
+        };
+        return (
+            Some(GraniteTypes::Camera3D(class)),
+            vec![TypeId::of::<Camera3d>(), TypeId::of::<Camera>()],
+        );
+    }
+
+    // Other meshes, e.g. glTF primitives, keep their DynamicSceneAssetPaths as a component.
+    // So do .obj meshes whose material is not a Granite material file, OBJ can not point at others
+    let asset_paths = find(TypeId::of::<DynamicSceneAssetPaths>())
+        .and_then(DynamicSceneAssetPaths::from_reflect)
+        .filter(|paths| {
+            paths
+                .mesh
+                .as_ref()
+                .is_some_and(|mesh| mesh.ends_with(".obj"))
+                && paths
+                    .material
+                    .as_ref()
+                    .is_none_or(|material| material.ends_with(".mat"))
+        });
+    if let Some(DynamicSceneAssetPaths {
+        mesh: Some(mesh_path),
+        material,
+    }) = asset_paths
+    {
+        let class = OBJ {
+            mesh_path: mesh_path.into(),
+            material: MaterialData::new(material.unwrap_or_default()),
+        };
+        return (
+            Some(GraniteTypes::OBJ(class)),
+            vec![TypeId::of::<DynamicSceneAssetPaths>()],
+        );
+    }
+
+    (None, Vec::new())
+}
+
+/// Serialize a DynamicScene to .scn.ron
+/// Components that cannot be serialized (other asset handles, mostly) are dropped with a warning
+pub fn dynamic_scene_to_ron(
+    mut scene: DynamicScene,
+    type_registry: &TypeRegistry,
+) -> Result<String, String> {
+    for dynamic_entity in scene.entities.iter_mut() {
+        dynamic_entity.components.retain(|component| {
+            let serializer = TypedReflectSerializer::new(component.as_ref(), type_registry);
+            match ron::to_string(&serializer) {
+                Ok(_) => true,
+                Err(e) => {
+                    log!(
+                        LogType::Game,
+                        LogLevel::Warning,
+                        LogCategory::System,
+                        "Dropped component {} on {} from exported scene: {}",
+                        component.reflect_type_path(),
+                        dynamic_entity.entity,
+                        e
+                    );
+                    false
+                }
+            }
+        });
+    }
+
+    scene.serialize(type_registry).map_err(|e| e.to_string())
+}
+
+/// Watches for RequestExportDynamicSceneEvent and writes the source as a .scn.ron, plus a .gltf
+/// next to it when meshes or materials were built in code
+pub fn export_dynamic_scene_system(
+    mut commands: Commands,
+    mut export_reader: EventReader<RequestExportDynamicSceneEvent>,
+) {
+    for request in export_reader.read() {
+        let source = request.source.clone();
+        let request_path = request.path.clone();
+        let path = rel_asset_to_absolute(&request.path).to_string();
+
+        commands.queue(move |world: &mut World| {
+            // Entity refs in game components are written as the uuid of their target
+            let exported = with_entity_uuids(world, |world| {
+                let type_registry = world.resource::<AppTypeRegistry>().read();
+                dynamic_scene_from_source(world, &source, &request_path).and_then(
+                    |DynamicSceneExport { scene, assets }| {
+                        let entity_count = scene.entities.len();
+                        let serialized = dynamic_scene_to_ron(scene, &type_registry)?;
+                        Ok((entity_count, serialized, assets))
+                    },
+                )
+            });
+            let written = exported.and_then(|(entity_count, serialized, assets)| {
+                if let Some(assets) = assets {
+                    let assets_path =
+                        rel_asset_to_absolute(&scene_assets_path(&request_path)).to_string();
+                    assets
+                        .write(Path::new(&assets_path))
+                        .map_err(|e| e.to_string())?;
+                }
+                if let Some(parent) = Path::new(&path).parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                fs::write(&path, serialized).map_err(|e| e.to_string())?;
+                Ok(entity_count)
+            });
+
+            match written {
+                Ok(entity_count) => log!(
+                    LogType::Game,
+                    LogLevel::OK,
+                    LogCategory::System,
+                    "Exported {} entities of '{}' to: {}",
+                    entity_count,
+                    source,
+                    path
+                ),
+                Err(e) => log!(
+                    LogType::Game,
+                    LogLevel::Error,
+                    LogCategory::System,
+                    "Failed to export '{}' to {}: {}",
+                    source,
+                    path,
+                    e
+                ),
+            }
+        });
+    }
+}
+
+/// Watches for RequestImportDynamicSceneEvent and spawns the .scn.ron as Granite entities
+pub fn import_dynamic_scene_system(
+    mut assets: SceneSpawnAssets,
+    mut commands: Commands,
+    type_registry: Res<AppTypeRegistry>,
+    mut metadata_store: ResMut<SceneMetadataStore>,
+    mut import_reader: EventReader<RequestImportDynamicSceneEvent>,
+    mut world_load_success_writer: EventWriter<WorldLoadSuccessEvent>,
+    mut world_load_failed_writer: EventWriter<WorldLoadFailedEvent>,
+) {
+    for request in import_reader.read() {
+        let path = rel_asset_to_absolute(&request.path).to_string();
+        let type_registry = type_registry.read();
+
+        let scene = fs::read_to_string(&path)
+            .map_err(|source| GraniteSceneError::Io {
+                path: path.clone(),
+                source,
+            })
+            .and_then(|contents| {
+                ron::Options::default()
+                    .from_str_seed(
+                        &contents,
+                        SceneDeserializer {
+                            type_registry: &type_registry,
+                        },
+                    )
+                    .map_err(|e| GraniteSceneError::from_ron(path.clone(), e))
+            });
+
+        match scene {
+            Ok(scene) => {
+                let entities = entities_from_dynamic_scene(&scene, &type_registry);
+                // Reflection already loaded these as the current types, so nothing needs migrating
+                let component_versions = current_component_versions(
+                    entities
+                        .iter()
+                        .filter_map(|entity| entity.components.as_ref())
+                        .flat_map(|components| components.keys()),
+                    &type_registry,
+                );
+                spawn_scene_entities(
+                    &mut assets,
+                    &mut commands,
+                    &entities,
+                    &component_versions,
+                    request.source.clone().into(),
+                    &SceneLoadOptions::default(),
+                );
+
+                log!(
+                    LogType::Game,
+                    LogLevel::OK,
+                    LogCategory::System,
+                    "Imported {} entities from {} into '{}'",
+                    entities.len(),
+                    path,
+                    request.source
+                );
+                let metadata = SceneMetadata {
+                    entity_count: entities.len(),
+                    ..default()
+                };
+                metadata_store.insert(request.source.clone(), metadata.clone());
+                world_load_success_writer
+                    .write(WorldLoadSuccessEvent(request.source.clone(), metadata));
+            }
+            Err(error) => {
+                log!(
+                    LogType::Game,
+                    LogLevel::Error,
+                    LogCategory::System,
+                    "Failed to import dynamic scene: {}",
+                    error
+                );
+                world_load_failed_writer.write(WorldLoadFailedEvent {
+                    path: request.path.clone(),
+                    error,
+                });
+            }
+        }
+    }
+}
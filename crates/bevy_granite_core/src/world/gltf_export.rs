@@ -0,0 +1,652 @@
+use crate::{
+    assets::EditableMaterial,
+    entities::{GraniteType, IdentityData, SpawnSource},
+    events::RequestExportGltfEvent,
+    shared::rel_asset_to_absolute,
+};
+use bevy::{
+    asset::AssetId,
+    pbr::{DirectionalLight, PointLight},
+    prelude::*,
+    reflect::Enum,
+    render::{
+        camera::{Camera, Projection},
+        mesh::{Indices, Mesh, Mesh3d, PrimitiveTopology, VertexAttributeValues},
+    },
+};
+use bevy_granite_logging::{
+    config::{LogCategory, LogLevel, LogType},
+    log,
+};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    f32::consts::PI,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+// gltf_export.rs
+// Writes the entities of a source as glTF 2.0, .glb when the path ends with it, otherwise .gltf
+// with the binary data next to it as a .bin. Meshes come from Assets<Mesh>, so every class that
+// spawns a Mesh3d is exported as it looks in the editor, materials come from the StandardMaterialDef
+// of the class and lights use KHR_lights_punctual.
+// Every node stores the Granite UUID and class in its extras, so a re-import can match nodes back up.
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+const COMPONENT_UNSIGNED_INT: u32 = 5125;
+const COMPONENT_FLOAT: u32 = 5126;
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const MODE_TRIANGLES: u32 = 4;
+
+/// A glTF document built from a source, ready to be written as .gltf or .glb
+pub struct GltfExport {
+    pub json: Value,
+    pub buffer: Vec<u8>,
+}
+
+impl GltfExport {
+    /// Write as .glb when the path ends with it, otherwise as .gltf plus a .bin with the same stem
+    pub fn write(mut self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let is_glb = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("glb"));
+
+        if self.buffer.is_empty() {
+            self.json.as_object_mut().unwrap().remove("buffers");
+        } else if !is_glb {
+            let bin_path = path.with_extension("bin");
+            let bin_name = bin_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            self.json["buffers"][0]["uri"] = json!(bin_name);
+            fs::write(&bin_path, &self.buffer)?;
+        }
+
+        if !is_glb {
+            let contents = serde_json::to_string_pretty(&self.json)?;
+            return fs::write(path, contents);
+        }
+
+        let mut json_chunk = serde_json::to_vec(&self.json)?;
+        pad_to_four(&mut json_chunk, b' ');
+        let mut bin_chunk = self.buffer;
+        pad_to_four(&mut bin_chunk, 0);
+
+        let mut total_length = 12 + 8 + json_chunk.len();
+        if !bin_chunk.is_empty() {
+            total_length += 8 + bin_chunk.len();
+        }
+
+        let mut glb = Vec::with_capacity(total_length);
+        glb.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(total_length as u32).to_le_bytes());
+        glb.extend_from_slice(&(json_chunk.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&GLB_CHUNK_JSON.to_le_bytes());
+        glb.extend_from_slice(&json_chunk);
+        if !bin_chunk.is_empty() {
+            glb.extend_from_slice(&(bin_chunk.len() as u32).to_le_bytes());
+            glb.extend_from_slice(&GLB_CHUNK_BIN.to_le_bytes());
+            glb.extend_from_slice(&bin_chunk);
+        }
+
+        fs::write(path, glb)
+    }
+}
+
+/// Build a glTF document from every Granite entity of a loaded source
+/// output_path is where the file will be written, texture URIs are made relative to it
+pub fn gltf_from_source(world: &mut World, source: &str, output_path: &Path) -> GltfExport {
+    let mut query = world.query::<(
+        Entity,
+        &IdentityData,
+        &SpawnSource,
+        &Transform,
+        Option<&GlobalTransform>,
+        Option<&ChildOf>,
+    )>();
+
+    let mut entities = Vec::new();
+    for (entity, identity, spawn_source, transform, global_transform, child_of) in query.iter(world)
+    {
+        if spawn_source.str_ref() != source {
+            continue;
+        }
+        entities.push((
+            entity,
+            identity.clone(),
+            *transform,
+            global_transform.map(|global| global.compute_transform()),
+            child_of.map(|child_of| child_of.parent()),
+        ));
+    }
+
+    let node_indices: HashMap<Entity, usize> = entities
+        .iter()
+        .enumerate()
+        .map(|(index, (entity, ..))| (*entity, index))
+        .collect();
+
+    let mut builder = GltfBuilder::new(output_path);
+    let mut nodes = Vec::with_capacity(entities.len());
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); entities.len()];
+    let mut roots = Vec::new();
+
+    for (index, (entity, identity, transform, world_transform, parent)) in
+        entities.iter().enumerate()
+    {
+        // Parents outside the source (e.g. loaded under another entity) become roots at their world position
+        let transform = match parent.and_then(|parent| node_indices.get(&parent)) {
+            Some(parent_index) => {
+                children[*parent_index].push(index);
+                *transform
+            }
+            None => {
+                roots.push(index);
+                if parent.is_some() {
+                    world_transform.unwrap_or(*transform)
+                } else {
+                    *transform
+                }
+            }
+        };
+
+        let mut node = Map::new();
+        node.insert("name".into(), json!(identity.name));
+        node.insert(
+            "translation".into(),
+            json!(transform.translation.to_array()),
+        );
+        node.insert("rotation".into(), json!(transform.rotation.to_array()));
+        node.insert("scale".into(), json!(transform.scale.to_array()));
+        node.insert(
+            "extras".into(),
+            json!({
+                "granite_uuid": identity.uuid.to_string(),
+                "granite_class": identity.class.variant_name(),
+            }),
+        );
+
+        let entity_ref = world.entity(*entity);
+
+        if let Some(mesh) = entity_ref.get::<Mesh3d>() {
+            let material = identity
+                .class
+                .get_material_data()
+                .map(|material| material.current);
+            let meshes = world.resource::<Assets<Mesh>>();
+            match builder.add_mesh(meshes, mesh.id(), &identity.name, material) {
+                Some(mesh_index) => {
+                    node.insert("mesh".into(), json!(mesh_index));
+                }
+                None => log!(
+                    LogType::Game,
+                    LogLevel::Warning,
+                    LogCategory::System,
+                    "Skipped mesh of '{}' in glTF export, it is not loaded or not a triangle list",
+                    identity.name
+                ),
+            }
+        }
+
+        let light = if let Some(light) = entity_ref.get::<PointLight>() {
+            // Bevy uses lumens for point lights, glTF candela
+            Some(json!({
+                "type": "point",
+                "color": linear_rgb(light.color),
+                "intensity": light.intensity / (4.0 * PI),
+                "range": light.range,
+            }))
+        } else {
+            entity_ref.get::<DirectionalLight>().map(|light| {
+                json!({
+                    "type": "directional",
+                    "color": linear_rgb(light.color),
+                    "intensity": light.illuminance,
+                })
+            })
+        };
+        if let Some(mut light) = light {
+            light["name"] = json!(identity.name);
+            builder.lights.push(light);
+            node.insert(
+                "extensions".into(),
+                json!({ "KHR_lights_punctual": { "light": builder.lights.len() - 1 } }),
+            );
+        }
+
+        if entity_ref.contains::<Camera>() {
+            let camera = match entity_ref.get::<Projection>() {
+                Some(Projection::Orthographic(orthographic)) => json!({
+                    "type": "orthographic",
+                    "orthographic": {
+                        "xmag": orthographic.area.width() / 2.0,
+                        "ymag": orthographic.area.height() / 2.0,
+                        "znear": orthographic.near,
+                        "zfar": orthographic.far,
+                    },
+                }),
+                Some(Projection::Perspective(perspective)) => json!({
+                    "type": "perspective",
+                    "perspective": {
+                        "yfov": perspective.fov,
+                        "aspectRatio": perspective.aspect_ratio,
+                        "znear": perspective.near,
+                        "zfar": perspective.far,
+                    },
+                }),
+                _ => {
+                    let perspective = PerspectiveProjection::default();
+                    json!({
+                        "type": "perspective",
+                        "perspective": {
+                            "yfov": perspective.fov,
+                            "znear": perspective.near,
+                            "zfar": perspective.far,
+                        },
+                    })
+                }
+            };
+            let mut camera = camera;
+            camera["name"] = json!(identity.name);
+            builder.cameras.push(camera);
+            node.insert("camera".into(), json!(builder.cameras.len() - 1));
+        }
+
+        nodes.push(node);
+    }
+
+    for (node, children) in nodes.iter_mut().zip(children) {
+        if !children.is_empty() {
+            node.insert("children".into(), json!(children));
+        }
+    }
+
+    builder.finish(nodes, roots)
+}
+
+/// Watches for RequestExportGltfEvent and writes the source as .gltf or .glb
+pub fn export_gltf_system(
+    mut commands: Commands,
+    mut export_reader: EventReader<RequestExportGltfEvent>,
+) {
+    for request in export_reader.read() {
+        let source = request.source.clone();
+        let path = rel_asset_to_absolute(&request.path).to_string();
+
+        commands.queue(move |world: &mut World| {
+            let export = gltf_from_source(world, &source, Path::new(&path));
+            let node_count = export.json["nodes"].as_array().map_or(0, Vec::len);
+
+            match export.write(Path::new(&path)) {
+                Ok(()) => log!(
+                    LogType::Game,
+                    LogLevel::OK,
+                    LogCategory::System,
+                    "Exported {} entities of '{}' to: {}",
+                    node_count,
+                    source,
+                    path
+                ),
+                Err(e) => log!(
+                    LogType::Game,
+                    LogLevel::Error,
+                    LogCategory::System,
+                    "Failed to export '{}' to {}: {}",
+                    source,
+                    path,
+                    e
+                ),
+            }
+        });
+    }
+}
+
+// Collects the glTF arrays while walking the entities, shared meshes and materials are written once
+// The DynamicScene export uses it too, for meshes and materials that have no asset path
+pub(crate) struct GltfBuilder {
+    output_dir: PathBuf,
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    meshes: Vec<Value>,
+    materials: Vec<Value>,
+    textures: Vec<Value>,
+    images: Vec<Value>,
+    lights: Vec<Value>,
+    cameras: Vec<Value>,
+    extensions_used: HashSet<&'static str>,
+    mesh_lookup: HashMap<(AssetId<Mesh>, Option<String>), usize>,
+    material_lookup: HashMap<String, usize>,
+    texture_lookup: HashMap<String, usize>,
+}
+
+impl GltfBuilder {
+    pub(crate) fn new(output_path: &Path) -> Self {
+        Self {
+            output_dir: output_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            buffer: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+            meshes: Vec::new(),
+            materials: Vec::new(),
+            textures: Vec::new(),
+            images: Vec::new(),
+            lights: Vec::new(),
+            cameras: Vec::new(),
+            extensions_used: HashSet::new(),
+            mesh_lookup: HashMap::new(),
+            material_lookup: HashMap::new(),
+            texture_lookup: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn finish(
+        mut self,
+        nodes: Vec<Map<String, Value>>,
+        roots: Vec<usize>,
+    ) -> GltfExport {
+        if !self.lights.is_empty() {
+            self.extensions_used.insert("KHR_lights_punctual");
+        }
+
+        let mut json = json!({
+            "asset": { "version": "2.0", "generator": "bevy_granite" },
+            "buffers": [{ "byteLength": self.buffer.len() }],
+        });
+
+        let root = json.as_object_mut().unwrap();
+        // Files that only hold meshes and materials have no scene
+        if !nodes.is_empty() {
+            root.insert("scene".into(), json!(0));
+            root.insert("scenes".into(), json!([{ "nodes": roots }]));
+            root.insert("nodes".into(), json!(nodes));
+        }
+        for (key, values) in [
+            ("bufferViews", self.buffer_views),
+            ("accessors", self.accessors),
+            ("meshes", self.meshes),
+            ("materials", self.materials),
+            ("textures", self.textures),
+            ("images", self.images),
+            ("cameras", self.cameras),
+        ] {
+            if !values.is_empty() {
+                root.insert(key.into(), json!(values));
+            }
+        }
+        if !self.lights.is_empty() {
+            root.insert(
+                "extensions".into(),
+                json!({ "KHR_lights_punctual": { "lights": self.lights } }),
+            );
+        }
+        if !self.extensions_used.is_empty() {
+            let mut extensions_used: Vec<_> = self.extensions_used.into_iter().collect();
+            extensions_used.sort();
+            root.insert("extensionsUsed".into(), json!(extensions_used));
+        }
+
+        GltfExport {
+            json,
+            buffer: self.buffer,
+        }
+    }
+
+    pub(crate) fn add_mesh(
+        &mut self,
+        meshes: &Assets<Mesh>,
+        mesh_id: AssetId<Mesh>,
+        name: &str,
+        material: Option<&EditableMaterial>,
+    ) -> Option<usize> {
+        let key = (mesh_id, material.map(|material| material.path.clone()));
+        if let Some(index) = self.mesh_lookup.get(&key) {
+            return Some(*index);
+        }
+
+        let mesh = meshes.get(mesh_id)?;
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return None;
+        }
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return None;
+        };
+
+        let mut min = Vec3::splat(f32::MAX);
+        let mut max = Vec3::splat(f32::MIN);
+        for position in positions {
+            min = min.min(Vec3::from_array(*position));
+            max = max.max(Vec3::from_array(*position));
+        }
+
+        let mut attributes = Map::new();
+        let position_accessor = self.add_accessor(
+            float_bytes(positions.iter().flatten()),
+            positions.len(),
+            "VEC3",
+            COMPONENT_FLOAT,
+            TARGET_ARRAY_BUFFER,
+        );
+        self.accessors[position_accessor]["min"] = json!(min.to_array());
+        self.accessors[position_accessor]["max"] = json!(max.to_array());
+        attributes.insert("POSITION".into(), json!(position_accessor));
+
+        if let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        {
+            let accessor = self.add_accessor(
+                float_bytes(normals.iter().flatten()),
+                normals.len(),
+                "VEC3",
+                COMPONENT_FLOAT,
+                TARGET_ARRAY_BUFFER,
+            );
+            attributes.insert("NORMAL".into(), json!(accessor));
+        }
+        if let Some(VertexAttributeValues::Float32x4(tangents)) =
+            mesh.attribute(Mesh::ATTRIBUTE_TANGENT)
+        {
+            let accessor = self.add_accessor(
+                float_bytes(tangents.iter().flatten()),
+                tangents.len(),
+                "VEC4",
+                COMPONENT_FLOAT,
+                TARGET_ARRAY_BUFFER,
+            );
+            attributes.insert("TANGENT".into(), json!(accessor));
+        }
+        if let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            let accessor = self.add_accessor(
+                float_bytes(uvs.iter().flatten()),
+                uvs.len(),
+                "VEC2",
+                COMPONENT_FLOAT,
+                TARGET_ARRAY_BUFFER,
+            );
+            attributes.insert("TEXCOORD_0".into(), json!(accessor));
+        }
+
+        let mut primitive = json!({ "attributes": attributes, "mode": MODE_TRIANGLES });
+        if let Some(indices) = mesh.indices() {
+            let indices: Vec<u32> = match indices {
+                Indices::U16(indices) => indices.iter().map(|index| *index as u32).collect(),
+                Indices::U32(indices) => indices.clone(),
+            };
+            let bytes = indices
+                .iter()
+                .flat_map(|index| index.to_le_bytes())
+                .collect();
+            let accessor = self.add_accessor(
+                bytes,
+                indices.len(),
+                "SCALAR",
+                COMPONENT_UNSIGNED_INT,
+                TARGET_ELEMENT_ARRAY_BUFFER,
+            );
+            primitive["indices"] = json!(accessor);
+        }
+        if let Some(material) = material {
+            primitive["material"] = json!(self.add_material(material));
+        }
+
+        self.meshes
+            .push(json!({ "name": name, "primitives": [primitive] }));
+        let index = self.meshes.len() - 1;
+        self.mesh_lookup.insert(key, index);
+        Some(index)
+    }
+
+    fn add_accessor(
+        &mut self,
+        bytes: Vec<u8>,
+        count: usize,
+        accessor_type: &str,
+        component_type: u32,
+        target: u32,
+    ) -> usize {
+        pad_to_four(&mut self.buffer, 0);
+        self.buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+            "target": target,
+        }));
+        self.buffer.extend_from_slice(&bytes);
+
+        self.accessors.push(json!({
+            "bufferView": self.buffer_views.len() - 1,
+            "componentType": component_type,
+            "count": count,
+            "type": accessor_type,
+        }));
+        self.accessors.len() - 1
+    }
+
+    // Unset fields fall back to Bevy's StandardMaterial defaults, not glTF's
+    // glTF packs metalness and roughness into one texture, so those two maps are not exported
+    pub(crate) fn add_material(&mut self, material: &EditableMaterial) -> usize {
+        if let Some(index) = self.material_lookup.get(&material.path) {
+            return *index;
+        }
+
+        let def = material.def.clone().unwrap_or_default();
+        let defaults = StandardMaterial::default();
+
+        let base_color = def
+            .base_color
+            .map(|(r, g, b, a)| Color::srgba(r, g, b, a))
+            .unwrap_or(defaults.base_color)
+            .to_linear();
+        let mut pbr = json!({
+            "baseColorFactor": base_color.to_f32_array(),
+            "metallicFactor": def.metalness.unwrap_or(defaults.metallic),
+            "roughnessFactor": def.roughness.unwrap_or(defaults.perceptual_roughness),
+        });
+        if let Some(texture) = self.add_texture(def.base_color_texture.as_deref()) {
+            pbr["baseColorTexture"] = json!({ "index": texture });
+        }
+
+        let mut gltf_material = json!({
+            "name": material.friendly_name,
+            "pbrMetallicRoughness": pbr,
+            "doubleSided": def.double_sided.unwrap_or(defaults.double_sided),
+        });
+        if let Some((r, g, b)) = def.emissive {
+            let emissive = Color::srgb(r, g, b).to_linear();
+            gltf_material["emissiveFactor"] = json!(emissive.to_f32_array_no_alpha());
+        }
+        if let Some(texture) = self.add_texture(def.emissive_texture.as_deref()) {
+            gltf_material["emissiveTexture"] = json!({ "index": texture });
+        }
+        if let Some(texture) = self.add_texture(def.normal_map_texture.as_deref()) {
+            gltf_material["normalTexture"] = json!({ "index": texture });
+        }
+        if let Some(texture) = self.add_texture(def.occlusion_map.as_deref()) {
+            gltf_material["occlusionTexture"] = json!({ "index": texture });
+        }
+        match def.alpha_mode.as_deref() {
+            Some("Blend") => gltf_material["alphaMode"] = json!("BLEND"),
+            Some("Mask") => {
+                gltf_material["alphaMode"] = json!("MASK");
+                gltf_material["alphaCutoff"] = json!(0.5);
+            }
+            _ => {}
+        }
+        if def.unlit.unwrap_or(defaults.unlit) {
+            gltf_material["extensions"] = json!({ "KHR_materials_unlit": {} });
+            self.extensions_used.insert("KHR_materials_unlit");
+        }
+
+        self.materials.push(gltf_material);
+        let index = self.materials.len() - 1;
+        self.material_lookup.insert(material.path.clone(), index);
+        index
+    }
+
+    // Images are referenced by URI relative to the exported file, not embedded
+    fn add_texture(&mut self, path: Option<&str>) -> Option<usize> {
+        let path = path.filter(|path| !path.is_empty())?;
+        if let Some(index) = self.texture_lookup.get(path) {
+            return Some(*index);
+        }
+
+        let absolute = rel_asset_to_absolute(path).to_string();
+        let uri = relative_uri(&self.output_dir, Path::new(&absolute));
+        self.images.push(json!({ "uri": uri }));
+        self.textures
+            .push(json!({ "source": self.images.len() - 1 }));
+
+        let index = self.textures.len() - 1;
+        self.texture_lookup.insert(path.to_string(), index);
+        Some(index)
+    }
+}
+
+fn linear_rgb(color: Color) -> [f32; 3] {
+    color.to_linear().to_f32_array_no_alpha()
+}
+
+fn float_bytes<'a>(values: impl Iterator<Item = &'a f32>) -> Vec<u8> {
+    values.flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn pad_to_four(bytes: &mut Vec<u8>, padding: u8) {
+    while !bytes.len().is_multiple_of(4) {
+        bytes.push(padding);
+    }
+}
+
+// Path from the directory of the exported file to target, with forward slashes as glTF expects
+fn relative_uri(from_dir: &Path, target: &Path) -> String {
+    let from: Vec<Component> = from_dir.components().collect();
+    let to: Vec<Component> = target.components().collect();
+    let common = from
+        .iter()
+        .zip(to.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
@@ -0,0 +1,46 @@
+use crate::entities::SceneMetadata;
+use bevy::prelude::Resource;
+use std::collections::{HashMap, HashSet};
+
+// metadata.rs
+// Metadata of every loaded scene, so a save writes back what was loaded (and edited since) instead
+// of starting from scratch. Keyed by source, the same relative path that ends up in SpawnSource.
+
+/// Metadata of loaded and saved scenes by source
+#[derive(Resource, Default, Debug, Clone)]
+pub struct SceneMetadataStore {
+    pub scenes: HashMap<String, SceneMetadata>,
+    /// Sources loaded from memory and not saved to a file since, they have no file behind them
+    pub in_memory: HashSet<String>,
+}
+
+impl SceneMetadataStore {
+    pub fn get(&self, source: &str) -> Option<&SceneMetadata> {
+        self.scenes.get(source)
+    }
+
+    pub fn get_mut(&mut self, source: &str) -> Option<&mut SceneMetadata> {
+        self.scenes.get_mut(source)
+    }
+
+    pub fn insert(&mut self, source: impl Into<String>, metadata: SceneMetadata) {
+        self.scenes.insert(source.into(), metadata);
+    }
+
+    pub fn is_in_memory(&self, source: &str) -> bool {
+        self.in_memory.contains(source)
+    }
+
+    pub fn set_in_memory(&mut self, source: &str, in_memory: bool) {
+        if in_memory {
+            self.in_memory.insert(source.to_string());
+        } else {
+            self.in_memory.remove(source);
+        }
+    }
+
+    /// Metadata for source, starting from the defaults when it has none yet
+    pub fn get_or_default(&mut self, source: &str) -> &mut SceneMetadata {
+        self.scenes.entry(source.to_string()).or_default()
+    }
+}
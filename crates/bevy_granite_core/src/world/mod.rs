@@ -1,12 +1,25 @@
+pub mod dynamic_scene;
+pub mod gltf_export;
+pub mod metadata;
 pub mod open;
 pub mod plugin;
 pub mod reload;
 pub mod save;
+pub mod scene_asset;
 
-pub use open::open_world_reader;
-pub use reload::reload_world_system;
+pub use dynamic_scene::{
+    dynamic_scene_from_source, dynamic_scene_to_ron, entities_from_dynamic_scene,
+    export_dynamic_scene_system, import_dynamic_scene_system, scene_assets_path,
+    DynamicSceneAssetPaths, DynamicSceneExport,
+};
+pub use gltf_export::{export_gltf_system, gltf_from_source, GltfExport};
+pub use metadata::SceneMetadataStore;
+pub use open::{open_world_reader, WorldLoadRequests, WorldLoadWriters};
+pub use plugin::WorldPlugin;
+pub use reload::{reload_world_system, restore_backup_system};
 pub use save::{
-    collect_components_system, save_data_ready_system, save_request_system, SaveWorldRequestData,
-    WorldState,
+    collect_components_system, save_copy_request_system, save_data_ready_system,
+    save_entities_request_system, save_request_system, serialize_to_string, SaveWorldRequestData,
+    SceneSaveSettings, WorldState,
 };
-pub use plugin::WorldPlugin;
\ No newline at end of file
+pub use scene_asset::{spawn_scene_asset_system, GraniteSceneAsset, GraniteSceneLoader};
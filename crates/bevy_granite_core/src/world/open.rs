@@ -1,29 +1,61 @@
-use crate::events::{RequestLoadEvent, WorldLoadSuccessEvent};
-use crate::{assets::AvailableEditableMaterials, entities::deserialize_entities};
-use bevy::{asset::io::file::FileAssetReader, prelude::*};
+use crate::events::{
+    RequestLoadEvent, RequestLoadFromMemoryEvent, RequestLoadWithOptionsEvent,
+    WorldLoadFailedEvent, WorldLoadSuccessEvent,
+};
+use crate::{
+    entities::{deserialize_entities, spawn_scene_entities, SceneLoadOptions, SceneSpawnAssets},
+    materials_from_folder_into_scene,
+    shared::{GranitePaths, SceneMigrations},
+    world::SceneMetadataStore,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_granite_logging::{
     config::{LogCategory, LogLevel, LogType},
     log,
 };
 use std::path::Path;
 
-/// Watches for RequestLoadEvent and then deserializes the world from its path
+/// Every way of asking for a scene to be loaded
+#[derive(SystemParam)]
+pub struct WorldLoadRequests<'w, 's> {
+    pub load: EventReader<'w, 's, RequestLoadEvent>,
+    pub load_with_options: EventReader<'w, 's, RequestLoadWithOptionsEvent>,
+    pub load_from_memory: EventReader<'w, 's, RequestLoadFromMemoryEvent>,
+}
+
+/// Writers for the outcome of a scene load
+#[derive(SystemParam)]
+pub struct WorldLoadWriters<'w> {
+    pub success: EventWriter<'w, WorldLoadSuccessEvent>,
+    pub failed: EventWriter<'w, WorldLoadFailedEvent>,
+}
+
+/// Watches for RequestLoadEvent and RequestLoadWithOptionsEvent then deserializes the world from its path
+/// RequestLoadFromMemoryEvent scenes are spawned the same way under their source
 pub fn open_world_reader(
-    asset_server: Res<AssetServer>,
+    mut assets: SceneSpawnAssets,
     mut commands: Commands,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    meshes: ResMut<Assets<Mesh>>,
-    mut available_materials: ResMut<AvailableEditableMaterials>,
-    mut world_open_reader: EventReader<RequestLoadEvent>,
-    mut world_load_success_writer: EventWriter<WorldLoadSuccessEvent>,
+    migrations: Res<SceneMigrations>,
+    paths: Res<GranitePaths>,
+    mut metadata_store: ResMut<SceneMetadataStore>,
+    mut requests: WorldLoadRequests,
+    mut load_writers: WorldLoadWriters,
 ) {
-    if let Some(RequestLoadEvent(path)) = world_open_reader.read().next() {
-        let abs_path: String;
+    let default_options = SceneLoadOptions::default();
+    let file_requests = requests
+        .load
+        .read()
+        .map(|RequestLoadEvent(path)| (path, &default_options))
+        .chain(
+            requests
+                .load_with_options
+                .read()
+                .map(|request| (&request.path, &request.options)),
+        );
+
+    for (path, options) in file_requests {
+        let abs_path = paths.to_absolute(path).to_string_lossy().to_string();
         if !Path::new(path).is_absolute() {
-            abs_path = FileAssetReader::get_base_path()
-                .join("assets/".to_string() + path)
-                .to_string_lossy()
-                .to_string();
             log!(
                 LogType::Game,
                 LogLevel::Info,
@@ -31,27 +63,100 @@ pub fn open_world_reader(
                 "Open world called: {:?}",
                 abs_path
             );
-        } else {
-            abs_path = path.to_string();
         }
 
-        deserialize_entities(
-            &asset_server,
+        match deserialize_entities(
+            &mut assets,
             &mut commands,
-            &mut materials,
-            &mut available_materials,
-            meshes,
-            abs_path,
-        );
+            &migrations,
+            abs_path.clone(),
+            options,
+        ) {
+            Ok(metadata) => {
+                log!(
+                    LogType::Game,
+                    LogLevel::OK,
+                    LogCategory::System,
+                    "Loaded world: {:?}",
+                    path
+                );
 
-        log!(
-            LogType::Game,
-            LogLevel::OK,
-            LogCategory::System,
-            "Loaded world: {:?}",
-            path
-        );
+                let source = paths.to_relative(&abs_path);
+                metadata_store.set_in_memory(&source, false);
+                metadata_store.insert(source, metadata.clone());
+                load_writers
+                    .success
+                    .write(WorldLoadSuccessEvent(path.to_string(), metadata));
+            }
+            Err(error) => {
+                log!(
+                    LogType::Game,
+                    LogLevel::Error,
+                    LogCategory::System,
+                    "Failed to load world: {}",
+                    error
+                );
+
+                load_writers.failed.write(WorldLoadFailedEvent {
+                    path: path.to_string(),
+                    error,
+                });
+            }
+        }
+    }
 
-        world_load_success_writer.write(WorldLoadSuccessEvent(path.to_string()));
+    for request in requests.load_from_memory.read() {
+        let loaded = request
+            .scene
+            .to_scene_data(&request.source, &migrations)
+            .map(|scene_data| {
+                materials_from_folder_into_scene(
+                    "materials",
+                    &mut assets.materials,
+                    &mut assets.available_materials,
+                    &assets.asset_server,
+                );
+                spawn_scene_entities(
+                    &mut assets,
+                    &mut commands,
+                    &scene_data.entities,
+                    &scene_data.metadata.component_versions,
+                    request.source.clone().into(),
+                    &request.options,
+                );
+                scene_data.metadata
+            });
+
+        match loaded {
+            Ok(metadata) => {
+                log!(
+                    LogType::Game,
+                    LogLevel::OK,
+                    LogCategory::System,
+                    "Loaded world from memory as: {:?}",
+                    request.source
+                );
+
+                metadata_store.set_in_memory(&request.source, true);
+                metadata_store.insert(request.source.clone(), metadata.clone());
+                load_writers
+                    .success
+                    .write(WorldLoadSuccessEvent(request.source.clone(), metadata));
+            }
+            Err(error) => {
+                log!(
+                    LogType::Game,
+                    LogLevel::Error,
+                    LogCategory::System,
+                    "Failed to load world from memory: {}",
+                    error
+                );
+
+                load_writers.failed.write(WorldLoadFailedEvent {
+                    path: request.source.clone(),
+                    error,
+                });
+            }
+        }
     }
 }
@@ -1,30 +1,72 @@
-use super::{open_world_reader, SaveWorldRequestData,
-    collect_components_system, reload_world_system, save_request_system, save_data_ready_system,
+use super::{
+    collect_components_system, export_dynamic_scene_system, export_gltf_system,
+    import_dynamic_scene_system, open_world_reader, reload_world_system, restore_backup_system,
+    save_copy_request_system, save_data_ready_system, save_entities_request_system,
+    save_request_system, spawn_scene_asset_system, GraniteSceneAsset, GraniteSceneLoader,
+    SaveWorldRequestData, SceneMetadataStore, SceneSaveSettings,
 };
+use crate::{entities::BridgeTag, shared::SceneMigrations};
 use bevy::{
     app::{App, Plugin, Update},
+    asset::AssetApp,
 };
+use bevy_granite_scene_assets::{DynamicSceneAssetPaths, SceneAssetsPlugin};
 
 pub struct WorldPlugin;
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
+        // Games may add it themselves to spawn exported scenes without Granite
+        if !app.is_plugin_added::<SceneAssetsPlugin>() {
+            app.add_plugins(SceneAssetsPlugin);
+        }
+
         app
             //
             // Resources
             //
             .init_resource::<SaveWorldRequestData>()
+            .init_resource::<SceneMigrations>()
+            .init_resource::<SceneSaveSettings>()
+            .init_resource::<SceneMetadataStore>()
+            //
+            // Register Types
+            // Imported meshes that are not an .obj keep their asset paths as a component
+            //
+            .register_type_data::<DynamicSceneAssetPaths, BridgeTag>()
+            //
+            // Assets
+            //
+            .init_asset::<GraniteSceneAsset>()
             //
             // Schedule system
             //
-            .add_systems(Update, open_world_reader)
+            .add_systems(
+                Update,
+                (
+                    open_world_reader,
+                    spawn_scene_asset_system,
+                    import_dynamic_scene_system,
+                    export_dynamic_scene_system,
+                    export_gltf_system,
+                ),
+            )
             .add_systems(
                 Update,
                 (
                     collect_components_system,
                     reload_world_system,
+                    restore_backup_system,
                     save_request_system,
+                    save_entities_request_system,
+                    save_copy_request_system,
                     save_data_ready_system,
                 ),
             );
     }
+
+    fn finish(&self, app: &mut App) {
+        // Loader runs off the main thread, so it gets its own copy of every migration registered during build
+        let migrations = app.world().resource::<SceneMigrations>().clone();
+        app.register_asset_loader(GraniteSceneLoader::new(migrations));
+    }
 }
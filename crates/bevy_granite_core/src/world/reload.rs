@@ -1,8 +1,19 @@
 use crate::{
-    entities::{despawn_recursive_serializable_entities, IdentityData},
-    events::{RequestLoadEvent, RequestReloadEvent},
+    entities::{
+        despawn_recursive_serializable_entities, despawn_source_entities, IdentityData,
+        SpawnSourceQuery,
+    },
+    error::GraniteSceneError,
+    events::{RequestLoadEvent, RequestReloadEvent, RequestRestoreBackupEvent},
+    shared::{absolute_asset_to_rel, backup_path, rel_asset_to_absolute, restore_scene_backup},
+    world::SceneSaveSettings,
+    WorldLoadFailedEvent,
+};
+use bevy::prelude::{Commands, Entity, EventReader, EventWriter, Query, Res, With};
+use bevy_granite_logging::{
+    config::{LogCategory, LogLevel, LogType},
+    log,
 };
-use bevy::prelude::{Commands, Entity, EventReader, EventWriter, Query, With};
 
 /// Despawns all entities then loads the world
 pub fn reload_world_system(
@@ -16,3 +27,51 @@ pub fn reload_world_system(
         load_world_writter.write(RequestLoadEvent(path.to_string()));
     }
 }
+
+/// Copies a backup over its scene, then despawns that scene's entities and loads it again
+pub fn restore_backup_system(
+    mut restore_reader: EventReader<RequestRestoreBackupEvent>,
+    mut commands: Commands,
+    source_query: SpawnSourceQuery,
+    save_settings: Res<SceneSaveSettings>,
+    mut load_world_writer: EventWriter<RequestLoadEvent>,
+    mut load_failed_writer: EventWriter<WorldLoadFailedEvent>,
+) {
+    for request in restore_reader.read() {
+        let abs_path = rel_asset_to_absolute(&request.path).to_string();
+        let backup = backup_path(&abs_path, request.backup);
+
+        if let Err(source) = restore_scene_backup(&abs_path, &backup, save_settings.backups) {
+            log!(
+                LogType::Game,
+                LogLevel::Error,
+                LogCategory::System,
+                "Failed to restore '{}' from '{}': {}",
+                request.path,
+                backup.display(),
+                source
+            );
+            load_failed_writer.write(WorldLoadFailedEvent {
+                path: request.path.clone(),
+                error: GraniteSceneError::Io {
+                    path: backup.display().to_string(),
+                    source,
+                },
+            });
+            continue;
+        }
+
+        log!(
+            LogType::Game,
+            LogLevel::OK,
+            LogCategory::System,
+            "Restored '{}' from '{}'",
+            request.path,
+            backup.display()
+        );
+
+        let source = absolute_asset_to_rel(abs_path);
+        despawn_source_entities(&mut commands, &source_query, &source);
+        load_world_writer.write(RequestLoadEvent(request.path.clone()));
+    }
+}
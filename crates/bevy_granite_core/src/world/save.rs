@@ -1,21 +1,39 @@
 use crate::{
-    entities::{serialize_entities, ComponentEditor, HasRuntimeData, IdentityData, SpawnSource},
-    events::{CollectRuntimeDataEvent, RequestSaveEvent, RuntimeDataReadyEvent},
-    shared::absolute_asset_to_rel,
-    WorldSaveSuccessEvent,
+    entities::{
+        collect_prefab_overrides, current_component_versions,
+        serialize::{scene_data_from_world_state, scene_pretty_config},
+        serialize_entities, with_entity_uuids, ComponentEditor, GraniteTypes, GraniteUuidIndex,
+        HasRuntimeData, IdentityData, PrefabMember, PreservedSceneData, SceneMetadata, SpawnSource,
+    },
+    error::GraniteSceneError,
+    events::{
+        CollectRuntimeDataEvent, RequestSaveCopyEvent, RequestSaveEntitiesEvent, RequestSaveEvent,
+        RuntimeDataReadyEvent,
+    },
+    shared::{absolute_asset_to_rel, GranitePaths, SceneCompression},
+    world::SceneMetadataStore,
+    WorldSaveFailedEvent, WorldSaveSuccessEvent,
 };
 use bevy::{
-    asset::io::file::FileAssetReader,
     ecs::entity::Entity,
-    prelude::{ChildOf, Commands, EventReader, EventWriter, Query, ResMut, Resource, World},
+    prelude::{
+        AppTypeRegistry, ChildOf, Children, Commands, EventReader, EventWriter, GlobalTransform,
+        Query, Res, ResMut, Resource, With, Without, World,
+    },
+    reflect::TypeRegistry,
     transform::components::Transform,
 };
 use bevy_granite_logging::{
     config::{LogCategory, LogLevel, LogType},
     log,
 };
+use ron::{ser::to_string_pretty, value::RawValue};
 use std::path::PathBuf;
-use std::{borrow::Cow, collections::HashMap};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+};
+use uuid::Uuid;
 
 #[derive(Default, Debug, Clone)]
 pub struct WorldState {
@@ -24,17 +42,81 @@ pub struct WorldState {
 
     // More difficult to get, so we do no have this off rip
     // We need to use World and the type registry to build and send event back saying its ready
-    pub component_data: Option<HashMap<Entity, HashMap<String, String>>>,
+    pub component_data: Option<HashMap<Entity, BTreeMap<String, Box<RawValue>>>>,
+
+    // Class RON of entities whose class this app does not know, gathered with the components
+    pub preserved_classes: HashMap<Entity, Box<RawValue>>,
+
+    // Current version of every versioned component being saved, written into the scene metadata
+    pub component_versions: BTreeMap<String, u32>,
+
+    // Uuid of each entity's parent from the GraniteUuidIndex, gathered with the components
+    // Entities whose parent is not saved along with them are left out and become roots
+    pub parent_uuids: HashMap<Entity, Uuid>,
 
     // Inside world runner, when gathered this flag gets set
     pub components_ready: bool,
 }
 
+/// How scenes are written to disk
+#[derive(Resource, Clone, Debug)]
+pub struct SceneSaveSettings {
+    /// Leave the file untouched when the serialized scene matches what is already on disk
+    pub skip_unchanged: bool,
+    /// Compress every saved scene this way, otherwise it is picked from the file extension
+    /// e.g. `.scene.zst`. Loading detects the compression by itself
+    pub compression: Option<SceneCompression>,
+    /// Previous versions to keep next to each scene, `name.scene.bak` then `.bak.2` and so on
+    /// 0 turns backups off, the save itself is always written to a temporary file and renamed
+    pub backups: usize,
+}
+
+impl Default for SceneSaveSettings {
+    fn default() -> Self {
+        Self {
+            skip_unchanged: true,
+            compression: None,
+            backups: 1,
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct SaveWorldRequestData {
     pub pending_saves: HashMap<Cow<'static, str>, (PathBuf, WorldState)>, // source -> (path, world_state)
+    pub pending_reassigns: HashMap<Cow<'static, str>, Vec<(Entity, Option<Transform>)>>, // source -> entities to move into it once saved, with the world transform of those detached from their parent
+    pub pending_copies: HashMap<Cow<'static, str>, String>, // copy path -> source it is a copy of
+    pub pending_entity_copies: HashSet<Cow<'static, str>>, // paths of entity saves that leave the entities where they are
 }
 
+// Serializable entities with the source they were spawned from
+type SourceEntityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static IdentityData,
+        Option<&'static Transform>,
+        Option<&'static ChildOf>,
+        &'static SpawnSource,
+    ),
+    Without<PrefabMember>,
+>;
+
+// Serializable entities with their world transform, for saves that can drop their parent
+type SaveEntityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static IdentityData,
+        Option<&'static Transform>,
+        Option<&'static GlobalTransform>,
+        Option<&'static ChildOf>,
+    ),
+    Without<PrefabMember>,
+>;
+
 /// Part 1.
 /// We gather all entities that are serializeable with
 /// IdentityData and Transform
@@ -45,17 +127,12 @@ pub fn save_request_system(
     mut save_request: ResMut<SaveWorldRequestData>,
     mut event_writer: EventWriter<CollectRuntimeDataEvent>,
     mut event_reader: EventReader<RequestSaveEvent>,
-    query: Query<(
-        Entity,
-        &IdentityData,
-        Option<&Transform>,
-        Option<&ChildOf>,
-        &SpawnSource,
-    )>,
+    paths: Res<GranitePaths>,
+    query: SourceEntityQuery,
 ) {
     // Process only one save request per frame to avoid conflicts
     if let Some(RequestSaveEvent(path)) = event_reader.read().next() {
-        let spawn_source = absolute_asset_to_rel(path.clone());
+        let spawn_source = Cow::from(paths.to_relative(path));
 
         log!(
             LogType::Editor,
@@ -71,18 +148,7 @@ pub fn save_request_system(
         // Part 1.
         // Gather all entities that are serializeable and contain IdentityData and Transform
         // Filter by SpawnSource to only include entities from the target source
-        let entities_data: Vec<(Entity, IdentityData, Transform, Option<Entity>)> = query
-            .iter()
-            .filter(|(_, _, _, _, source)| source.str_ref() == spawn_source)
-            .map(|(entity, obj, transform, relation, _)| {
-                (
-                    entity,
-                    obj.clone(),
-                    transform.cloned().unwrap_or_default(),
-                    relation.map(|r| r.parent()),
-                )
-            })
-            .collect();
+        let entities_data = source_entities_data(&query, &spawn_source);
 
         log!(
             LogType::Editor,
@@ -93,9 +159,7 @@ pub fn save_request_system(
             spawn_source
         );
 
-        let asset_path = FileAssetReader::get_base_path()
-            .join("assets")
-            .join(path.clone());
+        let asset_path = paths.to_absolute(path);
 
         log!(
             LogType::Editor,
@@ -111,6 +175,9 @@ pub fn save_request_system(
         let world_state = WorldState {
             entity_data: Some(entities_data),
             component_data: None,
+            preserved_classes: HashMap::new(),
+            component_versions: BTreeMap::new(),
+            parent_uuids: HashMap::new(),
             components_ready: false,
         };
 
@@ -128,12 +195,189 @@ pub fn save_request_system(
     }
 }
 
+/// Part 1. for RequestSaveCopyEvent
+/// Same as save_request_system, but written to another path. Pending under the copy's path
+/// so a regular save of the same source can run alongside it
+pub fn save_copy_request_system(
+    mut save_request: ResMut<SaveWorldRequestData>,
+    mut event_writer: EventWriter<CollectRuntimeDataEvent>,
+    mut event_reader: EventReader<RequestSaveCopyEvent>,
+    paths: Res<GranitePaths>,
+    query: SourceEntityQuery,
+) {
+    for request in event_reader.read() {
+        let spawn_source = Cow::from(paths.to_relative(&request.source));
+        let copy_key = Cow::from(paths.to_relative(&request.path));
+        if save_request.pending_saves.contains_key(&copy_key) {
+            continue;
+        }
+
+        let entities_data = source_entities_data(&query, &spawn_source);
+        log!(
+            LogType::Editor,
+            LogLevel::Info,
+            LogCategory::System,
+            "Save copy request for {} entities of '{}' to '{}'",
+            entities_data.len(),
+            spawn_source,
+            request.path
+        );
+
+        let world_state = WorldState {
+            entity_data: Some(entities_data),
+            component_data: None,
+            preserved_classes: HashMap::new(),
+            component_versions: BTreeMap::new(),
+            parent_uuids: HashMap::new(),
+            components_ready: false,
+        };
+        save_request
+            .pending_copies
+            .insert(copy_key.clone(), spawn_source.to_string());
+        save_request.pending_saves.insert(
+            copy_key.clone(),
+            (paths.to_absolute(&request.path), world_state),
+        );
+        event_writer.write(CollectRuntimeDataEvent(copy_key.to_string()));
+    }
+}
+
+// Entities of a source as (entity, identity, local transform, parent)
+fn source_entities_data(
+    query: &SourceEntityQuery,
+    spawn_source: &str,
+) -> Vec<(Entity, IdentityData, Transform, Option<Entity>)> {
+    query
+        .iter()
+        .filter(|(_, _, _, _, source)| source.str_ref() == spawn_source)
+        .map(|(entity, obj, transform, relation, _)| {
+            (
+                entity,
+                obj.clone(),
+                transform.cloned().unwrap_or_default(),
+                relation.map(|r| r.parent()),
+            )
+        })
+        .collect()
+}
+
+/// Part 1. for RequestSaveEntitiesEvent
+/// Same as save_request_system, but entities come from the request and their descendants
+/// instead of a SpawnSource. Parents outside the request are dropped, and those entities
+/// keep their world position as new roots. When reassigning, they are detached in the world too
+pub fn save_entities_request_system(
+    mut save_request: ResMut<SaveWorldRequestData>,
+    mut event_writer: EventWriter<CollectRuntimeDataEvent>,
+    mut event_reader: EventReader<RequestSaveEntitiesEvent>,
+    paths: Res<GranitePaths>,
+    query: SaveEntityQuery,
+    children_query: Query<&Children>,
+    source_query: Query<(), With<SpawnSource>>,
+) {
+    // Process only one save request per frame to avoid conflicts
+    if let Some(request) = event_reader.read().next() {
+        let spawn_source = Cow::from(paths.to_relative(&request.path));
+
+        let mut included = HashSet::new();
+        let mut ordered = Vec::new();
+        let mut stack: Vec<Entity> = request.entities.iter().rev().copied().collect();
+        while let Some(entity) = stack.pop() {
+            if !included.insert(entity) {
+                continue;
+            }
+            ordered.push(entity);
+            if let Ok(children) = children_query.get(entity) {
+                stack.extend(children.iter().rev());
+            }
+        }
+
+        let entities_data: Vec<(Entity, IdentityData, Transform, Option<Entity>)> = ordered
+            .iter()
+            .filter_map(|&entity| query.get(entity).ok())
+            .map(|(entity, obj, transform, global_transform, relation)| {
+                match relation.map(|r| r.parent()) {
+                    Some(parent) if included.contains(&parent) => (
+                        entity,
+                        obj.clone(),
+                        transform.cloned().unwrap_or_default(),
+                        Some(parent),
+                    ),
+                    _ => (
+                        entity,
+                        obj.clone(),
+                        global_transform
+                            .map(|global| global.compute_transform())
+                            .or(transform.cloned())
+                            .unwrap_or_default(),
+                        None,
+                    ),
+                }
+            })
+            .collect();
+
+        log!(
+            LogType::Editor,
+            LogLevel::Info,
+            LogCategory::System,
+            "Save request for {} entities into new source: '{}' (from path: '{}')",
+            entities_data.len(),
+            spawn_source,
+            request.path
+        );
+
+        // Prefab members are not saved themselves, but still move along with their instance
+        if request.reassign_source {
+            let detached: HashMap<Entity, Transform> = entities_data
+                .iter()
+                .filter(|(entity, _, _, parent)| {
+                    parent.is_none()
+                        && query
+                            .get(*entity)
+                            .is_ok_and(|(_, _, _, _, relation)| relation.is_some())
+                })
+                .map(|(entity, _, transform, _)| (*entity, *transform))
+                .collect();
+            save_request.pending_reassigns.insert(
+                spawn_source.clone(),
+                ordered
+                    .iter()
+                    .copied()
+                    .filter(|&entity| source_query.contains(entity))
+                    .map(|entity| (entity, detached.get(&entity).copied()))
+                    .collect(),
+            );
+        } else {
+            save_request
+                .pending_entity_copies
+                .insert(spawn_source.clone());
+        }
+
+        let asset_path = paths.to_absolute(&request.path);
+
+        let world_state = WorldState {
+            entity_data: Some(entities_data),
+            component_data: None,
+            preserved_classes: HashMap::new(),
+            component_versions: BTreeMap::new(),
+            parent_uuids: HashMap::new(),
+            components_ready: false,
+        };
+
+        save_request
+            .pending_saves
+            .insert(spawn_source.clone(), (asset_path, world_state));
+
+        event_writer.write(CollectRuntimeDataEvent(spawn_source.to_string()));
+    }
+}
+
 /// This is part 2 of world save request.
 /// We gather components
 /// Simplified version using the new consolidated patterns
 pub fn collect_components_system(
     mut commands: Commands,
-    runtime_query: Query<(Entity, &HasRuntimeData, &SpawnSource)>,
+    save_request: Res<SaveWorldRequestData>,
+    runtime_query: Query<(Entity, &HasRuntimeData, &SpawnSource), Without<PrefabMember>>,
     mut event_reader: EventReader<CollectRuntimeDataEvent>,
 ) {
     for CollectRuntimeDataEvent(spawn_source) in event_reader.read() {
@@ -145,10 +389,20 @@ pub fn collect_components_system(
             spawn_source
         );
 
-        // Filter entities to only include those with matching SpawnSource
+        // Entities gathered in part 1 if there are any, they can span several sources
+        // Otherwise filter entities to only include those with matching SpawnSource
+        let pending_entities: Option<HashSet<Entity>> = save_request
+            .pending_saves
+            .get(spawn_source.as_str())
+            .and_then(|(_, world_state)| world_state.entity_data.as_ref())
+            .map(|entity_data| entity_data.iter().map(|(entity, ..)| *entity).collect());
+
         let entities: Vec<Entity> = runtime_query
             .iter()
-            .filter(|(_, _, source)| source.str_ref() == *spawn_source)
+            .filter(|(entity, _, source)| match &pending_entities {
+                Some(pending_entities) => pending_entities.contains(entity),
+                None => source.str_ref() == *spawn_source,
+            })
             .map(|(entity, _, _)| entity)
             .collect();
 
@@ -166,51 +420,197 @@ pub fn collect_components_system(
 
         // Need access to world to get components
         commands.queue(move |world: &mut World| {
-            let component_editor = world.resource::<ComponentEditor>();
-            let mut collected_data = HashMap::new();
-
-            for entity in entities {
-                let serialized_components =
-                    component_editor.serialize_entity_components(world, entity);
-
-                if !serialized_components.is_empty() {
-                    collected_data.insert(entity, serialized_components);
-                }
-            }
+            let Some((path, mut world_state)) = world
+                .get_resource_mut::<SaveWorldRequestData>()
+                .and_then(|mut data| data.pending_saves.remove(&spawn_source_clone))
+            else {
+                return;
+            };
+            collect_runtime_data(world, &entities, &mut world_state);
+            world
+                .resource_mut::<SaveWorldRequestData>()
+                .pending_saves
+                .insert(spawn_source_clone.clone(), (path, world_state));
 
             log!(
                 LogType::Game,
                 LogLevel::Info,
-                LogCategory::Entity,
-                "Collected components: {:?}",
-                collected_data
+                LogCategory::System,
+                "Sending RuntimeDataReadyEvent for source: {}",
+                spawn_source_clone
             );
 
-            if let Some(mut data) = world.get_resource_mut::<SaveWorldRequestData>() {
-                if let Some((_, world_state)) = data.pending_saves.get_mut(&spawn_source_clone) {
-                    world_state.component_data = Some(collected_data);
-                    world_state.components_ready = true;
+            world.send_event(RuntimeDataReadyEvent(spawn_source_clone.to_string()));
+        });
+    }
+}
+
+// Part 2. with World access
+// Components, prefab overrides, preserved classes and component versions of the runtime entities
+fn collect_runtime_data(world: &mut World, entities: &[Entity], world_state: &mut WorldState) {
+    // Entity refs are written as the uuid of their target
+    let (collected_data, prefab_classes) = with_entity_uuids(world, |world| {
+        let component_editor = world.resource::<ComponentEditor>();
+        let mut collected_data = HashMap::new();
 
-                    log!(
-                        LogType::Game,
-                        LogLevel::Info,
-                        LogCategory::System,
-                        "Sending RuntimeDataReadyEvent for source: {}",
-                        spawn_source_clone
-                    );
+        for &entity in entities {
+            let serialized_components = component_editor.serialize_entity_components(world, entity);
 
-                    world.send_event(RuntimeDataReadyEvent(spawn_source_clone.to_string()));
-                }
+            if !serialized_components.is_empty() {
+                collected_data.insert(entity, serialized_components);
             }
+        }
+
+        log!(
+            LogType::Game,
+            LogLevel::Info,
+            LogCategory::Entity,
+            "Collected components: {:?}",
+            collected_data
+        );
+
+        // Prefab instances store what their members changed instead of the members themselves
+        let prefab_classes = collect_prefab_overrides(world, entities);
+        (collected_data, prefab_classes)
+    });
+
+    let component_versions = {
+        let type_registry = world.resource::<AppTypeRegistry>().read();
+        let override_components = prefab_classes.values().flat_map(|class| match class {
+            GraniteTypes::Prefab(prefab) => prefab
+                .overrides
+                .values()
+                .flat_map(|o| o.components.keys())
+                .collect(),
+            _ => Vec::new(),
         });
+        let mut component_versions = current_component_versions(
+            collected_data
+                .values()
+                .flat_map(|components| components.keys())
+                .chain(override_components),
+            &type_registry,
+        );
+        component_versions.extend(preserved_component_versions(
+            world,
+            entities,
+            &type_registry,
+        ));
+        component_versions
+    };
+
+    let preserved_classes: HashMap<Entity, Box<RawValue>> = entities
+        .iter()
+        .filter_map(|&entity| {
+            let class = world.get::<PreservedSceneData>(entity)?.class.clone()?;
+            let raw = RawValue::from_boxed_ron(class.into_boxed_str()).ok()?;
+            Some((entity, raw))
+        })
+        .collect();
+
+    let mut parent_uuids = HashMap::new();
+    if let Some(entity_data) = world_state.entity_data.as_mut() {
+        for (entity, identity, _, _) in entity_data.iter_mut() {
+            if let Some(class) = prefab_classes.get(entity) {
+                identity.class = class.clone();
+            }
+        }
+
+        let saved: HashSet<Entity> = entity_data.iter().map(|(entity, ..)| *entity).collect();
+        let uuid_index = world.resource::<GraniteUuidIndex>();
+        for (entity, _, _, parent) in entity_data.iter() {
+            if let Some(uuid) = parent
+                .filter(|parent| saved.contains(parent))
+                .and_then(|parent| uuid_index.uuid_of(parent))
+            {
+                parent_uuids.insert(*entity, uuid);
+            }
+        }
     }
+    world_state.component_data = Some(collected_data);
+    world_state.preserved_classes = preserved_classes;
+    world_state.component_versions = component_versions;
+    world_state.parent_uuids = parent_uuids;
+    world_state.components_ready = true;
+}
+
+// Preserved components were never migrated, so they are written back at the version they were saved at
+// There is one version per type, when the same type loaded fine elsewhere it keeps the current one
+fn preserved_component_versions(
+    world: &World,
+    entities: &[Entity],
+    type_registry: &TypeRegistry,
+) -> BTreeMap<String, u32> {
+    let is_live = |type_path: &str| {
+        type_registry
+            .get_with_type_path(type_path)
+            .is_some_and(|registration| {
+                entities.iter().any(|&entity| {
+                    world
+                        .entity(entity)
+                        .contains_type_id(registration.type_id())
+                })
+            })
+    };
+
+    let mut versions = BTreeMap::new();
+    for &entity in entities {
+        let Some(preserved) = world.get::<PreservedSceneData>(entity) else {
+            continue;
+        };
+        for (type_path, version) in preserved.component_versions.iter() {
+            if !is_live(type_path) {
+                versions.insert(type_path.clone(), *version);
+            }
+        }
+    }
+    versions
+}
+
+/// Serialize a loaded source to scene RON without touching the disk
+/// Same contents a save would write, with the metadata the source was loaded with
+pub fn serialize_to_string(world: &mut World, source: &str) -> Result<String, GraniteSceneError> {
+    let source = absolute_asset_to_rel(source.to_string());
+    let mut query_state = world.query_filtered::<(
+        Entity,
+        &IdentityData,
+        Option<&Transform>,
+        Option<&ChildOf>,
+        &SpawnSource,
+    ), Without<PrefabMember>>();
+    let entity_data = source_entities_data(&query_state.query(world), &source);
+    let entities: Vec<Entity> = entity_data
+        .iter()
+        .map(|(entity, ..)| *entity)
+        .filter(|&entity| world.get::<HasRuntimeData>(entity).is_some())
+        .collect();
+
+    let mut world_state = WorldState {
+        entity_data: Some(entity_data),
+        ..Default::default()
+    };
+    collect_runtime_data(world, &entities, &mut world_state);
+
+    let mut metadata = world
+        .get_resource::<SceneMetadataStore>()
+        .and_then(|store| store.get(&source).cloned())
+        .unwrap_or_default();
+    let scene_data = scene_data_from_world_state(world_state, &mut metadata);
+    to_string_pretty(&scene_data, scene_pretty_config()).map_err(|e| GraniteSceneError::Serialize {
+        path: source.to_string(),
+        message: e.to_string(),
+    })
 }
 
 /// Component data is ready, we can save the world
 pub fn save_data_ready_system(
+    mut commands: Commands,
     mut event_reader: EventReader<RuntimeDataReadyEvent>,
     mut save_request_data: ResMut<SaveWorldRequestData>,
+    save_settings: Res<SceneSaveSettings>,
+    mut metadata_store: ResMut<SceneMetadataStore>,
     mut saved_event_writer: EventWriter<WorldSaveSuccessEvent>,
+    mut save_failed_event_writer: EventWriter<WorldSaveFailedEvent>,
 ) {
     for RuntimeDataReadyEvent(source) in event_reader.read() {
         log!(
@@ -222,6 +622,9 @@ pub fn save_data_ready_system(
         );
         let source: &str = source.as_ref();
 
+        let reassign = save_request_data.pending_reassigns.remove(source);
+        let copy_of = save_request_data.pending_copies.remove(source);
+        let entity_copy = save_request_data.pending_entity_copies.remove(source);
         if let Some((path, world_state)) = save_request_data.pending_saves.remove(source) {
             if !world_state.components_ready {
                 log!(
@@ -241,15 +644,90 @@ pub fn save_data_ready_system(
                 "Components gathered and ready to save for source '{}'",
                 source
             );
-            serialize_entities(world_state, Some(path.display().to_string()));
-            log!(
-                LogType::Game,
-                LogLevel::OK,
-                LogCategory::System,
-                "Saved world: {:?}",
-                path
-            );
-            saved_event_writer.write(WorldSaveSuccessEvent(path.display().to_string()));
+            // Copies carry the source's metadata and compression, but never touch the source itself
+            let mut copy_metadata;
+            let copy_settings;
+            let (metadata, settings) = match copy_of.as_deref() {
+                Some(original) => {
+                    copy_metadata = metadata_store.get(original).cloned().unwrap_or_default();
+                    copy_settings = SceneSaveSettings {
+                        compression: save_settings
+                            .compression
+                            .or(Some(SceneCompression::from_path(original))),
+                        backups: 0,
+                        ..save_settings.clone()
+                    };
+                    (&mut copy_metadata, &copy_settings)
+                }
+                // Not a loaded source, so nothing to keep in the metadata store
+                None if entity_copy => {
+                    copy_metadata = SceneMetadata::default();
+                    (&mut copy_metadata, &*save_settings)
+                }
+                None => (metadata_store.get_or_default(source), &*save_settings),
+            };
+
+            match serialize_entities(
+                world_state,
+                Some(path.display().to_string()),
+                metadata,
+                settings,
+            ) {
+                Ok(()) if copy_of.is_some() => {
+                    log!(
+                        LogType::Game,
+                        LogLevel::OK,
+                        LogCategory::System,
+                        "Saved copy of '{}' to {:?}",
+                        copy_of.unwrap_or_default(),
+                        path
+                    );
+                }
+                Ok(()) if entity_copy => {
+                    log!(
+                        LogType::Game,
+                        LogLevel::OK,
+                        LogCategory::System,
+                        "Saved copy of entities to {:?}",
+                        path
+                    );
+                }
+                Ok(()) => {
+                    log!(
+                        LogType::Game,
+                        LogLevel::OK,
+                        LogCategory::System,
+                        "Saved world: {:?}",
+                        path
+                    );
+
+                    metadata_store.set_in_memory(source, false);
+
+                    // Saved entities now belong to the new scene, detached from parents left behind
+                    for (entity, detached) in reassign.into_iter().flatten() {
+                        if let Ok(mut entity_commands) = commands.get_entity(entity) {
+                            entity_commands.insert(SpawnSource::new(source.to_string()));
+                            if let Some(world_transform) = detached {
+                                entity_commands.remove::<ChildOf>().insert(world_transform);
+                            }
+                        }
+                    }
+                    saved_event_writer.write(WorldSaveSuccessEvent(path.display().to_string()));
+                }
+                Err(error) => {
+                    log!(
+                        LogType::Game,
+                        LogLevel::Error,
+                        LogCategory::System,
+                        "Failed to save world: {}",
+                        error
+                    );
+                    save_failed_event_writer.write(WorldSaveFailedEvent {
+                        path: path.display().to_string(),
+                        error,
+                    });
+                }
+            }
         } else {
             log!(
                 LogType::Game,
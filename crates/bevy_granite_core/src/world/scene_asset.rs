@@ -0,0 +1,172 @@
+use crate::events::{RequestSpawnSceneAssetEvent, WorldLoadFailedEvent, WorldLoadSuccessEvent};
+use crate::{
+    entities::{
+        deserialize::parse_scene_contents, spawn_scene_entities, EntitySaveReadyData,
+        SceneLoadOptions, SceneMetadata, SceneSpawnAssets,
+    },
+    error::GraniteSceneError,
+    materials_from_folder_into_scene,
+    shared::{decode_scene_bytes, SceneMigrations},
+    world::{SceneMetadataStore, WorldLoadWriters},
+};
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, LoadState},
+    prelude::*,
+};
+use bevy_granite_logging::{
+    config::{LogCategory, LogLevel, LogType},
+    log,
+};
+
+/// A .scene file loaded through the asset server
+/// Entities are already migrated to the current format and ready to spawn
+#[derive(Asset, TypePath, Debug)]
+pub struct GraniteSceneAsset {
+    pub metadata: SceneMetadata,
+    pub entities: Vec<EntitySaveReadyData>,
+}
+
+/// Loads .scene files (plain or compressed) through whatever AssetReader the app is configured with
+/// Holds a copy of the scene migrations taken when the WorldPlugin finishes building
+pub struct GraniteSceneLoader {
+    migrations: SceneMigrations,
+}
+
+impl GraniteSceneLoader {
+    pub fn new(migrations: SceneMigrations) -> Self {
+        Self { migrations }
+    }
+}
+
+impl AssetLoader for GraniteSceneLoader {
+    type Asset = GraniteSceneAsset;
+    type Settings = ();
+    type Error = GraniteSceneError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<GraniteSceneAsset, GraniteSceneError> {
+        let path = load_context.path().to_string_lossy().replace("\\", "/");
+
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|source| GraniteSceneError::Io {
+                path: path.clone(),
+                source,
+            })?;
+        let contents = decode_scene_bytes(&bytes).map_err(|source| GraniteSceneError::Io {
+            path: path.clone(),
+            source,
+        })?;
+
+        // Upgraded contents are never written back from here, the source may not be writable
+        let (scene, _) = parse_scene_contents(&contents, &path, &self.migrations)?;
+        Ok(GraniteSceneAsset {
+            metadata: scene.metadata,
+            entities: scene.entities,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["scene", "scene.zst", "scene.gz"]
+    }
+}
+
+/// Watches for RequestSpawnSceneAssetEvent and spawns the scene once its handle has loaded
+/// Handles that are still loading are kept around and checked again next frame
+/// The asset path becomes the source, handles without one fail to load
+pub fn spawn_scene_asset_system(
+    mut assets: SceneSpawnAssets,
+    mut commands: Commands,
+    scene_assets: Res<Assets<GraniteSceneAsset>>,
+    mut metadata_store: ResMut<SceneMetadataStore>,
+    mut pending: Local<Vec<Handle<GraniteSceneAsset>>>,
+    mut spawn_reader: EventReader<RequestSpawnSceneAssetEvent>,
+    mut load_writers: WorldLoadWriters,
+) {
+    pending.extend(spawn_reader.read().map(|request| request.0.clone()));
+
+    pending.retain(|handle| {
+        // Named sources are kept, e.g. `mods://levels/one.scene`
+        // Scenes added to Assets by hand have no path to use as their source
+        let Some(path) = handle
+            .path()
+            .map(|asset_path| asset_path.without_label().to_string().replace("\\", "/"))
+        else {
+            log!(
+                LogType::Game,
+                LogLevel::Error,
+                LogCategory::System,
+                "Scene asset {:?} has no path to use as its source",
+                handle.id()
+            );
+            load_writers.failed.write(WorldLoadFailedEvent {
+                path: String::new(),
+                error: GraniteSceneError::Asset {
+                    path: String::new(),
+                    message: "scene asset has no path, use RequestLoadFromMemoryEvent for scenes \
+                              built in code"
+                        .to_string(),
+                },
+            });
+            return false;
+        };
+
+        if let Some(scene) = scene_assets.get(handle) {
+            materials_from_folder_into_scene(
+                "materials",
+                &mut assets.materials,
+                &mut assets.available_materials,
+                &assets.asset_server,
+            );
+            spawn_scene_entities(
+                &mut assets,
+                &mut commands,
+                &scene.entities,
+                &scene.metadata.component_versions,
+                path.clone().into(),
+                &SceneLoadOptions::default(),
+            );
+
+            log!(
+                LogType::Game,
+                LogLevel::OK,
+                LogCategory::System,
+                "Spawned scene asset: {:?}",
+                path
+            );
+            metadata_store.set_in_memory(&path, false);
+            metadata_store.insert(path.clone(), scene.metadata.clone());
+            load_writers
+                .success
+                .write(WorldLoadSuccessEvent(path, scene.metadata.clone()));
+            return false;
+        }
+
+        if let LoadState::Failed(error) = assets.asset_server.load_state(handle) {
+            log!(
+                LogType::Game,
+                LogLevel::Error,
+                LogCategory::System,
+                "Failed to load scene asset {:?}: {}",
+                path,
+                error
+            );
+            load_writers.failed.write(WorldLoadFailedEvent {
+                path: path.clone(),
+                error: GraniteSceneError::Asset {
+                    path,
+                    message: error.to_string(),
+                },
+            });
+            return false;
+        }
+
+        true
+    });
+}
@@ -0,0 +1,196 @@
+use crate::editor_state::EditorState;
+use bevy::prelude::{EventReader, EventWriter, Res, ResMut, Resource, Time, Timer, TimerMode};
+use bevy_granite_core::{
+    absolute_asset_to_rel,
+    entities::{format_scene_data, parse_scene_data},
+    read_scene_file,
+    shared::rel_asset_to_absolute,
+    GranitePaths, RequestSaveCopyEvent, SceneMetadata, WorldLoadSuccessEvent,
+    WorldSaveSuccessEvent,
+};
+use bevy_granite_logging::{
+    config::{LogCategory, LogLevel, LogType},
+    log,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    path::{Component, Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+// autosave.rs
+// Every loaded source is saved to a recovery folder on a timer, the real scene files are never
+// touched. When a source is loaded and its autosave is newer and holds different entities, the
+// editor offers to restore it. Saving a source for real deletes its autosave.
+
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AutosaveSettings {
+    pub enabled: bool,
+    pub interval_secs: u32,
+    /// Asset path, like the editor config
+    pub recovery_dir: String,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 120,
+            recovery_dir: "config/recovery".to_string(),
+        }
+    }
+}
+
+/// An autosave newer than its source, waiting for the user to restore or discard it
+#[derive(Clone, Debug)]
+pub struct SceneRecovery {
+    pub source: String,
+    pub autosave: PathBuf,
+    pub modified: SystemTime,
+}
+
+#[derive(Resource)]
+pub struct AutosaveState {
+    timer: Timer,
+    /// Sources already checked for an autosave this session
+    checked: HashSet<String>,
+    pub recoveries: Vec<SceneRecovery>,
+}
+
+impl Default for AutosaveState {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(
+                AutosaveSettings::default().interval_secs as f32,
+                TimerMode::Repeating,
+            ),
+            checked: HashSet::new(),
+            recoveries: Vec::new(),
+        }
+    }
+}
+
+/// Where a source is autosaved, e.g. `assets/config/recovery/scenes/level.scene.autosave`
+/// Sources from named asset sources keep the source name as a folder
+pub fn autosave_path(settings: &AutosaveSettings, source: &str) -> PathBuf {
+    let mut path = GranitePaths::current().to_absolute(&settings.recovery_dir);
+    // Sources outside /assets keep their folders, minus the root
+    for component in Path::new(&source.replace("://", "/")).components() {
+        if let Component::Normal(part) = component {
+            path.push(part);
+        }
+    }
+    let mut name = OsString::from(path);
+    name.push(".autosave");
+    PathBuf::from(name)
+}
+
+/// Save every loaded source to the recovery folder once the interval has passed
+pub fn autosave_system(
+    time: Res<Time>,
+    mut state: ResMut<AutosaveState>,
+    editor_state: Res<EditorState>,
+    mut save_copy_writer: EventWriter<RequestSaveCopyEvent>,
+) {
+    let settings = &editor_state.config.autosave;
+    if !settings.enabled || editor_state.loaded_sources.is_empty() {
+        return;
+    }
+
+    let interval = Duration::from_secs(settings.interval_secs.max(1) as u64);
+    if state.timer.duration() != interval {
+        state.timer.set_duration(interval);
+        state.timer.reset();
+    }
+    if !state.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let mut autosaved = 0;
+    for source in editor_state.file_sources() {
+        // Keep the autosave the user has not decided on yet
+        if state.recoveries.iter().any(|r| &r.source == source) {
+            continue;
+        }
+        save_copy_writer.write(RequestSaveCopyEvent {
+            source: source.clone(),
+            path: autosave_path(settings, source).display().to_string(),
+        });
+        autosaved += 1;
+    }
+
+    log!(
+        LogType::Editor,
+        LogLevel::Info,
+        LogCategory::System,
+        "Autosaved {} source(s)",
+        autosaved
+    );
+}
+
+/// Look for an autosave the first time each source is loaded, and drop it once the source is saved
+pub fn find_scene_recoveries_system(
+    mut state: ResMut<AutosaveState>,
+    editor_state: Res<EditorState>,
+    mut load_success_reader: EventReader<WorldLoadSuccessEvent>,
+    mut save_success_reader: EventReader<WorldSaveSuccessEvent>,
+) {
+    let settings = &editor_state.config.autosave;
+
+    for WorldLoadSuccessEvent(path, _) in load_success_reader.read() {
+        let source = absolute_asset_to_rel(path.to_string()).to_string();
+        if !state.checked.insert(source.clone()) {
+            continue;
+        }
+
+        let autosave = autosave_path(settings, &source);
+        let Some(modified) = modified_time(&autosave) else {
+            continue;
+        };
+        let source_path = PathBuf::from(rel_asset_to_absolute(&source).as_ref());
+        let newer = modified_time(&source_path).is_none_or(|source_time| modified > source_time);
+        if newer && !same_entities(&autosave, &source_path) {
+            log!(
+                LogType::Editor,
+                LogLevel::Warning,
+                LogCategory::System,
+                "Found an autosave of '{}' newer than the scene: {}",
+                source,
+                autosave.display()
+            );
+            state.recoveries.push(SceneRecovery {
+                source,
+                autosave,
+                modified,
+            });
+        }
+    }
+
+    for WorldSaveSuccessEvent(path) in save_success_reader.read() {
+        let source = absolute_asset_to_rel(path.to_string()).to_string();
+        state
+            .recoveries
+            .retain(|recovery| recovery.source != source);
+        let _ = std::fs::remove_file(autosave_path(settings, &source));
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+// Compare as formatted scenes without metadata, so a different modified time alone is not a change
+fn same_entities(a: &Path, b: &Path) -> bool {
+    let formatted = |path: &Path| {
+        let contents = read_scene_file(path).ok()?;
+        let mut scene = parse_scene_data(&path.display().to_string(), &contents).ok()?;
+        scene.metadata = SceneMetadata::default();
+        format_scene_data(scene).ok()
+    };
+    matches!((formatted(a), formatted(b)), (Some(a), Some(b)) if a == b)
+}
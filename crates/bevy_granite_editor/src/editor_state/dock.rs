@@ -3,7 +3,8 @@ use crate::{
         BottomDockState, EditorSettingsTabData, SideDockState, SideTab
     }
 };
-use bevy::{asset::io::file::FileAssetReader, prelude::{EventReader, Res}};
+use bevy::prelude::{EventReader, Res};
+use bevy_granite_core::GranitePaths;
 use bevy::window::WindowClosing;
 use crate::utils::{load_from_toml_file, save_to_toml_file};
 use bevy_granite_logging::{
@@ -96,7 +97,7 @@ fn save_dock_layout_toml(
     }
 
     let config_path_buf =
-        FileAssetReader::get_base_path().join("assets/".to_string() + &editor_state.config_path);
+        GranitePaths::current().to_absolute(&editor_state.config_path);
     let dock_layout = get_dock_state_str(right_dock, bottom_dock);
 
     if let Some(config_path_str) = config_path_buf.to_str() {
@@ -5,13 +5,14 @@ use crate::{
 
 use crate::utils::{load_from_toml_file, save_to_toml_file};
 use bevy::ecs::event::EventReader;
-use bevy::{asset::io::file::FileAssetReader, prelude::ResMut};
+use bevy::prelude::{Res, ResMut};
 use bevy_granite_core::{
     absolute_asset_to_rel,
     events::{
         RequestDespawnBySource, RequestDespawnSerializableEntities, WorldLoadSuccessEvent,
         WorldSaveSuccessEvent,
     },
+    GranitePaths, SceneMetadataStore,
 };
 use bevy_granite_logging::{
     config::{LogCategory, LogLevel, LogType},
@@ -33,10 +34,13 @@ pub fn update_active_world_system(
     mut entities_despawned_by_source_reader: EventReader<RequestDespawnBySource>,
     mut set_active_world_reader: EventReader<SetActiveWorld>,
     mut editor_state: ResMut<EditorState>,
+    metadata_store: Res<SceneMetadataStore>,
 ) {
     for RequestDespawnSerializableEntities in entities_despawned_reader.read() {
         editor_state.current_file = None;
         editor_state.loaded_sources.clear();
+        editor_state.in_memory_sources.clear();
+        editor_state.unsaved_sources.clear();
         log!(
             LogType::Editor,
             LogLevel::OK,
@@ -47,6 +51,8 @@ pub fn update_active_world_system(
 
     for RequestDespawnBySource(source) in entities_despawned_by_source_reader.read() {
         editor_state.loaded_sources.remove(source);
+        editor_state.unsaved_sources.remove(source);
+        editor_state.in_memory_sources.remove(source);
 
         // If the current file was despawned, clear it
         if editor_state.current_file.as_ref() == Some(source) {
@@ -62,10 +68,15 @@ pub fn update_active_world_system(
         );
     }
 
-    for WorldLoadSuccessEvent(path) in open_success_reader.read() {
+    for WorldLoadSuccessEvent(path, _) in open_success_reader.read() {
         let rel_path = absolute_asset_to_rel(path.to_string());
         editor_state.current_file = Some(rel_path.to_string());
         editor_state.loaded_sources.insert(rel_path.to_string());
+        if metadata_store.is_in_memory(&rel_path) {
+            editor_state.in_memory_sources.insert(rel_path.to_string());
+        } else {
+            editor_state.in_memory_sources.remove(rel_path.as_ref());
+        }
         log!(
             LogType::Editor,
             LogLevel::Info,
@@ -79,6 +90,7 @@ pub fn update_active_world_system(
     for WorldSaveSuccessEvent(path) in world_save_success_reader.read() {
         let rel_path = absolute_asset_to_rel(path.to_string());
         editor_state.loaded_sources.insert(rel_path.to_string());
+        editor_state.in_memory_sources.remove(rel_path.as_ref());
         log!(
             LogType::Editor,
             LogLevel::Info,
@@ -126,8 +138,7 @@ pub fn save_editor_settings_from_widget_data(
     right_dock: SideDockState,
     bottom_dock: BottomDockState,
 ) {
-    let config_path_buf =
-        FileAssetReader::get_base_path().join("assets/".to_string() + &editor_state.config_path);
+    let config_path_buf = GranitePaths::current().to_absolute(&editor_state.config_path);
 
     editor_settings.dock.layout_str = get_dock_state_str(right_dock, bottom_dock);
 
@@ -174,8 +185,7 @@ pub fn update_editor_config_field<F>(
 where
     F: FnOnce(&mut EditorSettingsTabData),
 {
-    let config_path_buf =
-        FileAssetReader::get_base_path().join("assets/".to_string() + &editor_state.config_path);
+    let config_path_buf = GranitePaths::current().to_absolute(&editor_state.config_path);
 
     if let Some(config_path_str) = config_path_buf.to_str() {
         // Apply the update function to modify the config
@@ -218,8 +228,7 @@ where
 }
 
 pub fn load_editor_settings_toml(mut editor_state: ResMut<EditorState>) {
-    let config_path_buf =
-        FileAssetReader::get_base_path().join("assets/".to_string() + &editor_state.config_path);
+    let config_path_buf = GranitePaths::current().to_absolute(&editor_state.config_path);
     if let Some(config_path_str) = config_path_buf.to_str() {
         match load_from_toml_file(config_path_str) {
             Ok(editor_config_content) => {
@@ -0,0 +1,318 @@
+use crate::{editor_state::EditorState, interface::events::UserUpdatedComponentsEvent};
+use bevy::{
+    ecs::{change_detection::DetectChanges, system::SystemParam, world::Ref},
+    prelude::{
+        Changed, Commands, Entity, EventReader, EventWriter, Or, Query, Res, ResMut, Resource,
+        Time, Timer, TimerMode, Transform, With, Without,
+    },
+};
+use bevy_granite_core::{
+    absolute_asset_to_rel,
+    entities::{despawn_source_entities, SpawnSourceQuery},
+    events::RequestLoadEvent,
+    shared::rel_asset_to_absolute,
+    GraniteUuidIndex, IdentityData, MainCamera, SpawnSource, WorldLoadFailedEvent,
+    WorldLoadSuccessEvent, WorldSaveSuccessEvent,
+};
+use bevy_granite_gizmos::{
+    ActiveSelection, EntityEvent, RequestDuplicateAllSelectionEvent, RequestDuplicateEntityEvent,
+    Selected,
+};
+use bevy_granite_logging::{
+    config::{LogCategory, LogLevel, LogType},
+    log,
+};
+use std::{collections::HashMap, time::SystemTime};
+use uuid::Uuid;
+
+// hot_reload.rs
+// Opt-in watcher for scenes edited outside the editor (by hand, git pull, etc).
+// Every loaded source is polled for its modified time. When it changes, the source's entities are
+// despawned and the file is loaded again, with the selection restored by UUID afterwards.
+
+// How often loaded sources are checked on disk
+const POLL_INTERVAL_SECS: f32 = 1.0;
+
+#[derive(Resource)]
+pub struct SceneHotReloadState {
+    timer: Timer,
+    /// Last known modified time of every loaded source
+    modified: HashMap<String, SystemTime>,
+    /// Sources that were despawned and are waiting for their reload to finish
+    pending: Vec<PendingReload>,
+}
+
+impl Default for SceneHotReloadState {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(POLL_INTERVAL_SECS, TimerMode::Repeating),
+            modified: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+}
+
+struct PendingReload {
+    source: String,
+    selected: Vec<Uuid>,
+    active: Option<Uuid>,
+    current_file: Option<String>,
+    loaded: bool,
+}
+
+// Entities of a source moved or renamed in place, main camera is skipped as camera sync moves it every frame
+type EditedEntityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static SpawnSource,
+        Ref<'static, Transform>,
+        Ref<'static, IdentityData>,
+    ),
+    (
+        Or<(Changed<Transform>, Changed<IdentityData>)>,
+        Without<MainCamera>,
+    ),
+>;
+
+type SelectedSourceQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static IdentityData,
+        &'static SpawnSource,
+        Option<&'static ActiveSelection>,
+    ),
+    Or<(With<Selected>, With<ActiveSelection>)>,
+>;
+
+/// Editor edits that do not show up as a Transform or IdentityData change
+#[derive(SystemParam)]
+pub struct SourceEditEvents<'w, 's> {
+    components: EventReader<'w, 's, UserUpdatedComponentsEvent>,
+    duplicate: EventReader<'w, 's, RequestDuplicateEntityEvent>,
+    duplicate_selection: EventReader<'w, 's, RequestDuplicateAllSelectionEvent>,
+}
+
+/// Our own scene saves and loads, plus the load a reload asks for
+#[derive(SystemParam)]
+pub struct HotReloadEvents<'w, 's> {
+    save_success: EventReader<'w, 's, WorldSaveSuccessEvent>,
+    load_success: EventReader<'w, 's, WorldLoadSuccessEvent>,
+    load_failed: EventReader<'w, 's, WorldLoadFailedEvent>,
+    load: EventWriter<'w, RequestLoadEvent>,
+}
+
+/// Flag a source as changed in the editor since it was last saved
+pub fn mark_source_unsaved(editor_state: &mut EditorState, source: &str) {
+    if !editor_state.unsaved_sources.contains(source) {
+        editor_state.unsaved_sources.insert(source.to_string());
+    }
+}
+
+fn modified_time(source: &str) -> Option<SystemTime> {
+    std::fs::metadata(rel_asset_to_absolute(source).as_ref())
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Flag sources that were edited in the editor since they were last saved
+/// Transform and identity edits are picked up here, as are component edits and duplicates. Editor
+/// spawns and deletes flag their source where they happen
+pub fn track_unsaved_changes_system(
+    mut editor_state: ResMut<EditorState>,
+    mut save_success_reader: EventReader<WorldSaveSuccessEvent>,
+    mut edit_events: SourceEditEvents,
+    changed_query: EditedEntityQuery,
+    source_query: Query<&SpawnSource>,
+    selected_query: Query<&SpawnSource, With<Selected>>,
+) {
+    for WorldSaveSuccessEvent(path) in save_success_reader.read() {
+        let rel_path = absolute_asset_to_rel(path.to_string());
+        editor_state.unsaved_sources.remove(rel_path.as_ref());
+    }
+
+    for (source, transform, identity) in changed_query.iter() {
+        let edited = (transform.is_changed() && !transform.is_added())
+            || (identity.is_changed() && !identity.is_added());
+        if edited {
+            mark_source_unsaved(&mut editor_state, source.str_ref());
+        }
+    }
+
+    let edited_entities = edit_events
+        .components
+        .read()
+        .map(|event| event.entity)
+        .chain(edit_events.duplicate.read().map(|event| event.entity));
+    for source in edited_entities.filter_map(|entity| source_query.get(entity).ok()) {
+        mark_source_unsaved(&mut editor_state, source.str_ref());
+    }
+
+    if edit_events.duplicate_selection.read().count() > 0 {
+        for source in selected_query.iter() {
+            mark_source_unsaved(&mut editor_state, source.str_ref());
+        }
+    }
+}
+
+/// Poll loaded sources on disk and reload the ones that changed externally
+/// Sources with unsaved changes are never reloaded, a warning is logged instead
+pub fn hot_reload_scenes_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut state: ResMut<SceneHotReloadState>,
+    editor_state: Res<EditorState>,
+    mut events: HotReloadEvents,
+    source_query: SpawnSourceQuery,
+    selection_query: SelectedSourceQuery,
+) {
+    if !editor_state.config.hot_reload_scenes {
+        state.modified.clear();
+        state.pending.clear();
+        events.save_success.clear();
+        events.load_success.clear();
+        events.load_failed.clear();
+        return;
+    }
+
+    for WorldLoadFailedEvent { path, .. } in events.load_failed.read() {
+        let rel_path = absolute_asset_to_rel(path.to_string());
+        // Entities are already gone, nothing left to reselect. Next change on disk tries again
+        state.pending.retain(|pending| pending.source != rel_path);
+    }
+
+    // Our own saves and loads are not external changes
+    for path in events
+        .save_success
+        .read()
+        .map(|WorldSaveSuccessEvent(path)| path)
+        .chain(
+            events
+                .load_success
+                .read()
+                .map(|WorldLoadSuccessEvent(path, _)| path),
+        )
+    {
+        let rel_path = absolute_asset_to_rel(path.to_string()).to_string();
+        if let Some(modified) = modified_time(&rel_path) {
+            state.modified.insert(rel_path.clone(), modified);
+        }
+        for pending in state.pending.iter_mut() {
+            if pending.source == rel_path {
+                pending.loaded = true;
+            }
+        }
+    }
+
+    if !state.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    state.modified.retain(|source, _| {
+        editor_state.loaded_sources.contains(source)
+            && !editor_state.in_memory_sources.contains(source)
+    });
+
+    for source in editor_state.file_sources() {
+        let Some(modified) = modified_time(source) else {
+            continue;
+        };
+        let Some(known) = state.modified.insert(source.clone(), modified) else {
+            // First time we see this source, nothing to compare against yet
+            continue;
+        };
+        if known == modified || state.pending.iter().any(|p| &p.source == source) {
+            continue;
+        }
+
+        if editor_state.unsaved_sources.contains(source) {
+            log!(
+                LogType::Editor,
+                LogLevel::Warning,
+                LogCategory::System,
+                "Scene '{}' changed on disk but has unsaved changes in the editor, not reloading",
+                source
+            );
+            continue;
+        }
+
+        let mut selected = Vec::new();
+        let mut active = None;
+        for (identity, entity_source, is_active) in selection_query.iter() {
+            if entity_source.str_ref() != source {
+                continue;
+            }
+            if is_active.is_some() {
+                active = Some(identity.uuid);
+            } else {
+                selected.push(identity.uuid);
+            }
+        }
+
+        // Not RequestDespawnBySource, that would drop the source from the editor state
+        let despawned_count = despawn_source_entities(&mut commands, &source_query, source);
+
+        log!(
+            LogType::Editor,
+            LogLevel::Info,
+            LogCategory::System,
+            "Scene '{}' changed on disk, reloading {} entities",
+            source,
+            despawned_count
+        );
+
+        state.pending.push(PendingReload {
+            source: source.clone(),
+            selected,
+            active,
+            current_file: editor_state.current_file.clone(),
+            loaded: false,
+        });
+        events.load.write(RequestLoadEvent(source.clone()));
+    }
+}
+
+/// Reselect entities of reloaded scenes by UUID once their entities exist again
+/// Runs a frame after the load succeeded so the spawn commands have been applied
+pub fn restore_hot_reload_selection_system(
+    mut commands: Commands,
+    mut state: ResMut<SceneHotReloadState>,
+    mut editor_state: ResMut<EditorState>,
+    uuid_index: Res<GraniteUuidIndex>,
+) {
+    let (ready, waiting): (Vec<PendingReload>, Vec<PendingReload>) =
+        state.pending.drain(..).partition(|pending| pending.loaded);
+    state.pending = waiting;
+
+    for pending in ready {
+        let in_source = |uuid: &Uuid| {
+            uuid_index
+                .entry(*uuid)
+                .filter(|entry| entry.source.as_deref() == Some(pending.source.as_str()))
+                .map(|entry| entry.entity)
+        };
+
+        let selected: Vec<Entity> = pending.selected.iter().filter_map(in_source).collect();
+
+        match pending.active.as_ref().and_then(in_source) {
+            Some(active) => {
+                let mut range = vec![active];
+                range.extend(selected);
+                commands.trigger(EntityEvent::SelectRange {
+                    range,
+                    additive: true,
+                });
+            }
+            None => {
+                for entity in selected {
+                    commands.entity(entity).insert(Selected);
+                }
+            }
+        }
+
+        // Loading marks the reloaded scene as active, keep whatever was active before
+        editor_state.current_file = pending.current_file;
+        editor_state.unsaved_sources.remove(&pending.source);
+    }
+}
@@ -1,13 +1,25 @@
+pub mod autosave;
+pub mod config;
 pub mod dock;
 pub mod editor;
+pub mod hot_reload;
 pub mod plugin;
-pub mod config;
 
+pub use autosave::{
+    autosave_path, autosave_system, find_scene_recoveries_system, AutosaveSettings, AutosaveState,
+    SceneRecovery,
+};
+pub use config::*;
 pub use dock::{
     get_dock_state_str, load_dock_state, save_dock_on_window_close_system, DockLayoutStr,
 };
-pub use config::*;
 pub use editor::{
-    load_editor_settings_toml, save_editor_settings_from_widget_data, update_active_world_system, update_editor_vis_system, update_editor_config_field};
-    
-pub use plugin::{EditorState, ConfigPlugin};
+    load_editor_settings_toml, save_editor_settings_from_widget_data, update_active_world_system,
+    update_editor_config_field, update_editor_vis_system,
+};
+
+pub use hot_reload::{
+    hot_reload_scenes_system, mark_source_unsaved, restore_hot_reload_selection_system,
+    track_unsaved_changes_system, HotReloadEvents, SceneHotReloadState, SourceEditEvents,
+};
+pub use plugin::{ConfigPlugin, EditorState};
@@ -7,7 +7,10 @@ use bevy::{
 use super::editor::update_editor_vis_system;
 use crate::{
     editor_state::{
-        load_editor_settings_toml, save_dock_on_window_close_system, update_active_world_system,
+        autosave_system, find_scene_recoveries_system, hot_reload_scenes_system,
+        load_editor_settings_toml, restore_hot_reload_selection_system,
+        save_dock_on_window_close_system, track_unsaved_changes_system, update_active_world_system,
+        AutosaveState, SceneHotReloadState,
     },
     interface::EditorSettingsTabData,
     setup::is_editor_active,
@@ -26,6 +29,21 @@ pub struct EditorState {
 
     /// Track all loaded sources (world files/paths) that have entities spawned
     pub loaded_sources: std::collections::HashSet<String>,
+
+    /// Loaded sources whose entities were edited since they were last loaded or saved
+    pub unsaved_sources: std::collections::HashSet<String>,
+
+    /// Loaded sources that came from memory, there is no file to save, autosave or watch
+    pub in_memory_sources: std::collections::HashSet<String>,
+}
+
+impl EditorState {
+    /// Loaded sources that have a file behind them
+    pub fn file_sources(&self) -> impl Iterator<Item = &String> {
+        self.loaded_sources
+            .iter()
+            .filter(|source| !self.in_memory_sources.contains(*source))
+    }
 }
 
 pub struct ConfigPlugin {
@@ -49,13 +67,31 @@ impl Plugin for ConfigPlugin {
                 config_loaded: false,
                 layout_loaded: false,
                 loaded_sources: std::collections::HashSet::new(),
+                unsaved_sources: std::collections::HashSet::new(),
+                in_memory_sources: std::collections::HashSet::new(),
             })
+            .init_resource::<SceneHotReloadState>()
+            .init_resource::<AutosaveState>()
             //
             // Systems
             //
             .add_systems(PostStartup, load_editor_settings_toml)
             .add_systems(Update, update_active_world_system.run_if(is_editor_active))
             .add_systems(Update, save_dock_on_window_close_system)
-            .add_systems(Update, update_editor_vis_system);
+            .add_systems(Update, update_editor_vis_system)
+            .add_systems(
+                Update,
+                (
+                    track_unsaved_changes_system,
+                    restore_hot_reload_selection_system,
+                    hot_reload_scenes_system,
+                )
+                    .chain()
+                    .run_if(is_editor_active),
+            )
+            .add_systems(
+                Update,
+                (find_scene_recoveries_system, autosave_system).run_if(is_editor_active),
+            );
     }
 }
@@ -1,19 +1,19 @@
-use crate::{editor_state::EditorState, interface::UserRequestGraniteTypeViaPopup};
+use crate::{
+    editor_state::{mark_source_unsaved, EditorState},
+    interface::UserRequestGraniteTypeViaPopup,
+};
 use bevy::{
-    asset::{AssetServer, Assets},
     ecs::{
         event::EventReader,
         system::{Commands, Res, ResMut},
     },
-    pbr::StandardMaterial,
     prelude::Resource,
-    render::mesh::Mesh,
     transform::components::Transform,
 };
 use bevy_granite_core::{
-    entities::{GraniteType, SpawnSource},
+    entities::{GraniteType, SceneSpawnAssets, SpawnSource},
     shared::asset_file_browser_multiple,
-    AvailableEditableMaterials, GraniteTypes, PromptData, PromptImportSettings,
+    GraniteTypes, PromptData, PromptImportSettings,
 };
 use bevy_granite_gizmos::selection::events::EntityEvent;
 use bevy_granite_logging::{log, LogCategory, LogLevel, LogType};
@@ -104,13 +104,17 @@ pub fn new_entity_via_popup_system(
 pub fn process_entity_spawn_queue_system(
     mut spawn_queue: ResMut<EntitySpawnQueue>,
     mut commands: Commands,
-    available_materials: ResMut<AvailableEditableMaterials>,
-    standard_materials: ResMut<Assets<StandardMaterial>>,
-    meshes: ResMut<Assets<Mesh>>,
-    asset_server: Res<AssetServer>,
+    assets: SceneSpawnAssets,
     prompt_settings: Res<PromptImportSettings>,
+    mut editor_state: ResMut<EditorState>,
 ) {
     if let Some(mut pending) = spawn_queue.pending.pop_front() {
+        let SceneSpawnAssets {
+            asset_server,
+            materials,
+            available_materials,
+            meshes,
+        } = assets;
         let prompt_data = PromptData {
             file: pending.file,
             import_settings: prompt_settings.clone(),
@@ -119,7 +123,7 @@ pub fn process_entity_spawn_queue_system(
         let entity = pending.class.spawn_from_new_identity(
             &mut commands,
             pending.transform,
-            standard_materials,
+            materials,
             meshes,
             available_materials,
             asset_server,
@@ -130,6 +134,7 @@ pub fn process_entity_spawn_queue_system(
         commands
             .entity(entity)
             .insert(SpawnSource::new(pending.source.clone()));
+        mark_source_unsaved(&mut editor_state, &pending.source);
 
         let additive = pending.batch_size > 1;
         let remaining = spawn_queue.pending.len();
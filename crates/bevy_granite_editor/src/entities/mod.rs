@@ -1,10 +1,12 @@
 pub mod bounds;
 pub mod creation;
 pub mod relationship;
+pub mod save_selection;
 pub mod plugin;
 
 pub use bounds::{get_entity_bounds, get_entity_bounds_or_fallback};
 pub use creation::{new_entity_via_popup_system, process_entity_spawn_queue_system, EntitySpawnQueue, PendingEntitySpawn};
 pub use relationship::{child_removal_system, parent_removal_system, parent_removal_from_entities_system, parent_system, parent_from_node_tree_system};
+pub use save_selection::save_selection_system;
 
 pub use plugin::AssetPlugin;
\ No newline at end of file
@@ -1,7 +1,7 @@
 use super::{
     child_removal_system, new_entity_via_popup_system, parent_from_node_tree_system,
     parent_removal_from_entities_system, parent_removal_system, parent_system,
-    process_entity_spawn_queue_system, EntitySpawnQueue,
+    process_entity_spawn_queue_system, save_selection_system, EntitySpawnQueue,
 };
 use crate::setup::is_editor_active;
 use bevy::{
@@ -30,6 +30,7 @@ impl Plugin for AssetPlugin {
                     child_removal_system,
                     parent_removal_system,
                     parent_removal_from_entities_system,
+                    save_selection_system,
                 )
                     .run_if(is_editor_active),
             );
@@ -0,0 +1,47 @@
+use crate::interface::events::RequestSaveSelection;
+use bevy::ecs::{
+    entity::Entity,
+    event::{EventReader, EventWriter},
+    query::{Or, With},
+    system::Query,
+};
+use bevy_granite_core::RequestSaveEntitiesEvent;
+use bevy_granite_gizmos::{ActiveSelection, Selected};
+use bevy_granite_logging::*;
+
+type SelectionQuery<'w, 's> = Query<'w, 's, Entity, Or<(With<Selected>, With<ActiveSelection>)>>;
+
+pub fn save_selection_system(
+    mut save_selection_request: EventReader<RequestSaveSelection>,
+    mut save_entities_writer: EventWriter<RequestSaveEntitiesEvent>,
+    selection: SelectionQuery,
+) {
+    for request in save_selection_request.read() {
+        let entities: Vec<Entity> = selection.iter().collect();
+        if entities.is_empty() {
+            log!(
+                LogType::Editor,
+                LogLevel::Warning,
+                LogCategory::Entity,
+                "Nothing selected to save into '{}'",
+                request.path
+            );
+            continue;
+        }
+
+        log!(
+            LogType::Editor,
+            LogLevel::Info,
+            LogCategory::Entity,
+            "Saving {} selected entities into '{}'",
+            entities.len(),
+            request.path
+        );
+
+        save_entities_writer.write(RequestSaveEntitiesEvent {
+            path: request.path.clone(),
+            entities,
+            reassign_source: request.reassign_source,
+        });
+    }
+}
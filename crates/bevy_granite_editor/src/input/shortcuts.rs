@@ -1,14 +1,16 @@
 use bevy::{
     math::Vec2,
-    prelude::{Children, Commands, Entity, Query, Res},
+    prelude::{Children, Commands, Entity, Query, Res, ResMut},
+};
+use bevy_granite_core::{
+    RequestLoadEvent, RequestReloadEvent, RequestSaveEvent, SpawnSource, UserInput,
 };
-use bevy_granite_core::{RequestLoadEvent, RequestReloadEvent, RequestSaveEvent, UserInput};
 use bevy_granite_gizmos::{selection::events::EntityEvent, Selected};
 use bevy_granite_logging::{log, LogCategory, LogLevel, LogType};
 use native_dialog::FileDialog;
 
 use crate::{
-    editor_state::EditorState,
+    editor_state::{mark_source_unsaved, EditorState},
     interface::{
         events::{
             PopupMenuRequestedEvent, RequestCameraEntityFrame, RequestEditorToggle,
@@ -19,21 +21,38 @@ use crate::{
     },
 };
 
+type SelectedEntityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Selected,
+        Option<&'static Children>,
+        Option<&'static SpawnSource>,
+    ),
+>;
+
 pub fn shortcuts_system(
     mut commands: Commands,
     input: Res<UserInput>,
-    query: Query<(Entity, &Selected, Option<&Children>)>,
+    query: SelectedEntityQuery,
     mut events: EditorEvents,
-    editor_state: Res<EditorState>,
+    mut editor_state: ResMut<EditorState>,
 ) {
-    handle_shortcuts(&input, &editor_state, &mut commands, &query, &mut events);
+    handle_shortcuts(
+        &input,
+        &mut editor_state,
+        &mut commands,
+        &query,
+        &mut events,
+    );
 }
 
 fn handle_shortcuts(
     input: &UserInput,
-    editor_state: &EditorState,
+    editor_state: &mut EditorState,
     commands: &mut Commands,
-    query: &Query<(Entity, &Selected, Option<&Children>)>,
+    query: &SelectedEntityQuery,
     events: &mut EditorEvents,
 ) {
     // F1
@@ -73,13 +92,16 @@ fn handle_shortcuts(
             LogCategory::Input,
             "(shortcut) Deleting active entity"
         );
-        for (entity, _, children) in query.iter() {
+        for (entity, _, children, source) in query.iter() {
             if let Some(children) = children {
                 for &child in children.iter() {
                     commands.entity(child).despawn();
                 }
             }
             commands.entity(entity).despawn();
+            if let Some(source) = source {
+                mark_source_unsaved(editor_state, source.str_ref());
+            }
         }
     }
 
@@ -151,7 +173,7 @@ fn handle_shortcuts(
             "(shortcut) Opening load world dialog"
         );
         if let Some(path) = FileDialog::new()
-            .add_filter("Granite Scene", &["scene"])
+            .add_filter("Granite Scene", &["scene", "zst", "gz"])
             .show_open_single_file()
             .unwrap()
         {
@@ -168,7 +190,7 @@ fn handle_shortcuts(
         && !input.mouse_right.any
         && !input.mouse_left.any
     {
-        let loaded = &editor_state.loaded_sources;
+        let loaded: Vec<&String> = editor_state.file_sources().collect();
         if !loaded.is_empty() {
             log!(
                 LogType::Editor,
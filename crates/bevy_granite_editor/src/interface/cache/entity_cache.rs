@@ -3,7 +3,7 @@ use bevy::{
     pbr::{MeshMaterial3d, StandardMaterial},
     prelude::{Entity, Resource},
 };
-use bevy_granite_core::{IdentityData, TransformData};
+use bevy_granite_core::{IdentityData, PreservedSceneData, TransformData};
 use bevy_granite_gizmos::DragState;
 
 #[derive(Resource, Default, Clone)]
@@ -21,6 +21,7 @@ pub struct EntityData {
     pub identity: IdentityData,
     pub gizmo_drag: DragState,
     pub registered: EntityRegisteredData,
+    pub preserved: Option<PreservedSceneData>,
 }
 
 #[derive(Default, Clone)]
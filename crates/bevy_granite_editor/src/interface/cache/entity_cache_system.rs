@@ -7,7 +7,7 @@ use bevy::{
     prelude::{Entity, Name, Transform, World},
     transform::components::GlobalTransform,
 };
-use bevy_granite_core::{ComponentEditor, IdentityData, TransformData};
+use bevy_granite_core::{ComponentEditor, IdentityData, PreservedSceneData, TransformData};
 use bevy_granite_gizmos::{ActiveSelection, DragState};
 
 pub type EntityCacheQueryItem<'a> = (
@@ -74,6 +74,7 @@ pub fn update_entity_cache_system(world: &mut World) {
                 registered_data_changed: false,
             },
             gizmo_drag: gizmo_drag.clone(),
+            preserved: world.get::<PreservedSceneData>(entity).cloned(),
         };
         Some((entity, new_data))
     } else {
@@ -111,6 +112,9 @@ pub fn update_entity_cache_system(world: &mut World) {
             cache.data.identity = new_data.identity.clone();
             cache.dirty.identity_dirty = true;
         }
+        if cache.data.preserved != new_data.preserved {
+            cache.data.preserved = new_data.preserved;
+        }
         if cache.data.entity != new_data.entity {
             cache.data.entity = new_data.entity;
         }
@@ -134,6 +138,7 @@ pub fn update_entity_cache_system(world: &mut World) {
         cache.data.global_transform = TransformData::default();
         cache.data.identity = IdentityData::default();
         cache.data.gizmo_drag = DragState::default();
+        cache.data.preserved = None;
         // Clear registered components when no entity is selected
         cache.data.registered = EntityRegisteredData::default();
     }
@@ -8,7 +8,9 @@ use bevy::prelude::{Entity, Event, Vec2};
 use bevy_granite_core::RequestDespawnBySource;
 use bevy_granite_core::RequestDespawnSerializableEntities;
 use bevy_granite_core::{EditableMaterial, GraniteTypes};
-use bevy_granite_core::{RequestLoadEvent, RequestReloadEvent, RequestSaveEvent};
+use bevy_granite_core::{
+    RequestLoadEvent, RequestReloadEvent, RequestRestoreBackupEvent, RequestSaveEvent,
+};
 
 #[derive(SystemParam)]
 pub struct EditorEvents<'w> {
@@ -25,6 +27,8 @@ pub struct EditorEvents<'w> {
     pub remove_children: EventWriter<'w, RequestRemoveChildren>,
     pub despawn_all: EventWriter<'w, RequestDespawnSerializableEntities>,
     pub despawn_by_source: EventWriter<'w, RequestDespawnBySource>,
+    pub save_selection: EventWriter<'w, RequestSaveSelection>,
+    pub restore_backup: EventWriter<'w, RequestRestoreBackupEvent>,
 }
 
 // Internal Events
@@ -102,3 +106,11 @@ pub struct RequestRemoveParentsFromEntities {
 
 #[derive(Event)]
 pub struct RequestRemoveChildren;
+
+/// Save the selected entities and their descendants to a new scene
+/// With reassign_source the entities are moved into the new scene instead of copied
+#[derive(Event)]
+pub struct RequestSaveSelection {
+    pub path: String,
+    pub reassign_source: bool,
+}
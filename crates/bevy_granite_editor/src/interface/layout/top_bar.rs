@@ -12,6 +12,7 @@ use crate::{
         popups::PopupType,
         tabs::{
             debug::ui::DebugTabData, log::LogTabData, EditorSettingsTabData, EntityEditorTabData,
+            ScenePropertiesTabData,
         },
         EditorEvents, NodeTreeTabData,
     },
@@ -19,12 +20,15 @@ use crate::{
 };
 use bevy::{ecs::system::Commands, prelude::ResMut};
 use bevy_egui::egui;
+use crate::interface::events::RequestSaveSelection;
 use bevy_granite_core::{
-    RequestDespawnBySource, RequestDespawnSerializableEntities, RequestLoadEvent, RequestSaveEvent,
-    UserInput,
+    scene_backups, shared::rel_asset_to_absolute, RequestDespawnBySource,
+    RequestDespawnSerializableEntities, RequestLoadEvent, RequestRestoreBackupEvent,
+    RequestSaveEvent, UserInput,
 };
 use bevy_granite_gizmos::selection::events::EntityEvent;
 use native_dialog::FileDialog;
+use std::path::Path;
 
 pub fn top_bar_ui(
     side_dock: &mut ResMut<SideDockState>,
@@ -45,7 +49,7 @@ pub fn top_bar_ui(
             ui.menu_button("File", |ui| {
                 if ui.button("Save as").clicked() {
                     if let Some(path) = FileDialog::new()
-                        .add_filter("Granite Scene", &["scene"])
+                        .add_filter("Granite Scene", &["scene", "zst", "gz"])
                         .show_save_single_file()
                         .unwrap()
                     {
@@ -56,19 +60,36 @@ pub fn top_bar_ui(
                     ui.close();
                 }
 
-                if ui.button("Save (Ctrl + S)").clicked() {
-                    let loaded = &editor_state.loaded_sources;
-                    if !loaded.is_empty() {
-                        for source in loaded.iter() {
-                            events.save.write(RequestSaveEvent(source.to_string()));
+                ui.menu_button("Save Selection As", |ui| {
+                    for (label, reassign_source) in
+                        [("Copy to new scene", false), ("Move to new scene", true)]
+                    {
+                        if ui.button(label).clicked() {
+                            if let Some(path) = FileDialog::new()
+                                .add_filter("Granite Scene", &["scene", "zst", "gz"])
+                                .show_save_single_file()
+                                .unwrap()
+                            {
+                                events.save_selection.write(RequestSaveSelection {
+                                    path: path.display().to_string(),
+                                    reassign_source,
+                                });
+                            }
+                            ui.close();
                         }
                     }
+                });
+
+                if ui.button("Save (Ctrl + S)").clicked() {
+                    for source in editor_state.file_sources() {
+                        events.save.write(RequestSaveEvent(source.to_string()));
+                    }
                     ui.close();
                 }
 
                 if ui.button("Open (Ctrl + O)").clicked() {
                     if let Some(path) = FileDialog::new()
-                        .add_filter("Granite Scene", &["scene"])
+                        .add_filter("Granite Scene", &["scene", "zst", "gz"])
                         .show_open_single_file()
                         .unwrap()
                     {
@@ -79,6 +100,33 @@ pub fn top_bar_ui(
                     ui.close();
                 }
 
+                ui.menu_button("Restore from Backup", |ui| {
+                    if editor_state.loaded_sources.is_empty() {
+                        ui.label("  (No sources loaded)");
+                    }
+
+                    let mut sources: Vec<&String> = editor_state.loaded_sources.iter().collect();
+                    sources.sort();
+                    for source in sources {
+                        ui.menu_button(source, |ui| {
+                            let backups = scene_backups(rel_asset_to_absolute(source).as_ref());
+                            if backups.is_empty() {
+                                ui.label("  (No backups)");
+                            }
+
+                            for (index, backup) in backups.iter().enumerate() {
+                                if ui.button(backup_label(backup)).clicked() {
+                                    events.restore_backup.write(RequestRestoreBackupEvent {
+                                        path: source.clone(),
+                                        backup: index + 1,
+                                    });
+                                    ui.close();
+                                }
+                            }
+                        });
+                    }
+                });
+
                 ui.separator();
 
                 ui.menu_button("Despawn", |ui| {
@@ -167,6 +215,19 @@ pub fn top_bar_ui(
                     ui.close();
                 }
 
+                if !side_dock
+                    .dock_state
+                    .iter_all_tabs()
+                    .any(|(_, tab)| matches!(tab, SideTab::SceneProperties { .. }))
+                    && ui.button("Scene Properties").clicked()
+                {
+                    let tab = SideTab::SceneProperties {
+                        data: Box::new(ScenePropertiesTabData::default()),
+                    };
+                    side_dock.dock_state.push_to_focused_leaf(tab);
+                    ui.close();
+                }
+
                 if !bottom_dock
                     .dock_state
                     .iter_all_tabs()
@@ -244,3 +305,25 @@ pub fn top_bar_ui(
         ui.add_space(spacing);
     });
 }
+
+// e.g. "level.scene.bak (5 min ago)"
+fn backup_label(backup: &Path) -> String {
+    let name = backup
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let age = std::fs::metadata(backup)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .map(|elapsed| match elapsed.as_secs() {
+            0..60 => "just now".to_string(),
+            secs @ 60..3600 => format!("{} min ago", secs / 60),
+            secs @ 3600..86400 => format!("{} h ago", secs / 3600),
+            secs => format!("{} days ago", secs / 86400),
+        });
+    match age {
+        Some(age) => format!("{} ({})", name, age),
+        None => name,
+    }
+}
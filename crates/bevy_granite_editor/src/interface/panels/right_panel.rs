@@ -7,7 +7,8 @@ use serde::{Deserialize, Serialize};
 use crate::interface::{
     tabs::{
         editor_settings::ui::editor_settings_tab_ui, entity_editor::tab::entity_editor_tab_ui,
-        node_tree::ui::node_tree_tab_ui, EditorSettingsTabData, EntityEditorTabData, NodeTreeTabData
+        node_tree::ui::node_tree_tab_ui, scene_properties_tab_ui, EditorSettingsTabData,
+        EntityEditorTabData, NodeTreeTabData, ScenePropertiesTabData,
     },
 };
 
@@ -27,8 +28,12 @@ impl Default for SideDockState {
         let entity_editor_tab = SideTab::EntityEditor {
             data: Box::new(EntityEditorTabData::default()),
         };
+        let scene_properties_tab = SideTab::SceneProperties {
+            data: Box::new(ScenePropertiesTabData::default()),
+        };
 
-        let mut dock_state = DockState::new(vec![node_tree_tab, editor_settings_tab]);
+        let mut dock_state =
+            DockState::new(vec![node_tree_tab, scene_properties_tab, editor_settings_tab]);
         let surface = dock_state.main_surface_mut();
         let [_old_node, _entity_editor_node] =
             surface.split_below(NodeIndex::root(), 0.3, vec![entity_editor_tab]);
@@ -51,6 +56,10 @@ pub enum SideTab {
         #[serde(skip)]
         data: Box<EditorSettingsTabData>,
     },
+    SceneProperties {
+        #[serde(skip)]
+        data: Box<ScenePropertiesTabData>,
+    },
 }
 
 #[derive(Resource)]
@@ -70,6 +79,9 @@ impl TabViewer for SideTabViewer {
             SideTab::EntityEditor { data } => {
                 entity_editor_tab_ui(ui, data);
             }
+            SideTab::SceneProperties { data } => {
+                scene_properties_tab_ui(ui, data);
+            }
         }
     }
 
@@ -78,6 +90,7 @@ impl TabViewer for SideTabViewer {
             SideTab::NodeTree { .. } => "Entities".into(),
             SideTab::EditorSettings { .. } => "Settings".into(),
             SideTab::EntityEditor { .. } => "Entity Editor".into(),
+            SideTab::SceneProperties { .. } => "Scene Properties".into(),
         }
     }
 }
@@ -3,18 +3,21 @@ use super::{
     events::{
         MaterialDeleteEvent, MaterialHandleUpdateEvent, PopupMenuRequestedEvent,
         RequestCameraEntityFrame, RequestEditorToggle, RequestNewParent, RequestRemoveChildren,
-        RequestRemoveParents, RequestToggleCameraSync, SetActiveWorld,
+        RequestRemoveParents, RequestSaveSelection, RequestToggleCameraSync, SetActiveWorld,
         UserRequestGraniteTypeViaPopup, UserUpdatedComponentsEvent, UserUpdatedIdentityEvent,
         UserUpdatedTransformEvent,
     },
     layout::dock_ui_system,
-    popups::{handle_popup_requests_system, show_active_popups_system},
+    popups::{
+        handle_popup_requests_system, show_active_popups_system, show_recovery_prompt_system,
+    },
     tabs::{
         handle_material_deletion_system, update_debug_tab_ui_system,
         update_editor_settings_tab_system, update_entity_editor_tab_system,
         update_entity_with_new_components_system, update_entity_with_new_identity_system,
         update_entity_with_new_transform_system, update_log_tab_system,
-        update_material_handle_system, update_node_tree_tabs_system, RequestReparentEntityEvent,
+        update_material_handle_system, update_node_tree_tabs_system,
+        update_scene_properties_tab_system, RequestReparentEntityEvent,
     },
     BottomDockState, EntityUIDataCache, PopupState, SideDockState,
 };
@@ -45,6 +48,7 @@ impl Plugin for InterfacePlugin {
             .add_event::<RequestNewParent>()
             .add_event::<RequestRemoveChildren>()
             .add_event::<RequestRemoveParents>()
+            .add_event::<RequestSaveSelection>()
             .add_event::<SetActiveWorld>()
             // need to rework
             .add_event::<RequestReparentEntityEvent>()
@@ -90,12 +94,14 @@ impl Plugin for InterfacePlugin {
                     dock_ui_system,
                     handle_popup_requests_system,
                     show_active_popups_system,
+                    show_recovery_prompt_system,
                     //
                     // Interface tabs UI
                     //
                     update_node_tree_tabs_system,
                     update_entity_editor_tab_system,
                     update_editor_settings_tab_system,
+                    update_scene_properties_tab_system,
                     update_log_tab_system,
                     update_debug_tab_ui_system,
                     update_node_tree_tabs_system,
@@ -1,9 +1,11 @@
 pub mod add_entity_ui;
-pub mod relationship_ui;
-pub mod popup_requested_system;
 pub mod help_ui;
+pub mod popup_requested_system;
+pub mod recovery_ui;
+pub mod relationship_ui;
 
 pub use add_entity_ui::*;
-pub use relationship_ui::*;
+pub use help_ui::*;
 pub use popup_requested_system::*;
-pub use help_ui::*;
\ No newline at end of file
+pub use recovery_ui::*;
+pub use relationship_ui::*;
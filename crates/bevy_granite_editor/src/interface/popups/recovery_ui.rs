@@ -0,0 +1,105 @@
+use crate::{
+    editor_state::{AutosaveState, SceneRecovery},
+    interface::shared::widgets::make_frame_solid_via_context,
+    UI_CONFIG,
+};
+use bevy::prelude::{Commands, EventWriter, Res, ResMut};
+use bevy_egui::{
+    egui::{self, Window},
+    EguiContexts,
+};
+use bevy_granite_core::{
+    entities::{despawn_source_entities, SpawnSourceQuery},
+    restore_scene_backup,
+    shared::rel_asset_to_absolute,
+    RequestLoadEvent, SceneSaveSettings,
+};
+use bevy_granite_logging::{
+    config::{LogCategory, LogLevel, LogType},
+    log,
+};
+
+/// Offer to restore autosaves that are newer than their scene
+/// Restoring keeps the scene file as its newest backup, then reloads the source
+pub fn show_recovery_prompt_system(
+    mut contexts: EguiContexts,
+    mut state: ResMut<AutosaveState>,
+    mut commands: Commands,
+    source_query: SpawnSourceQuery,
+    mut load_writer: EventWriter<RequestLoadEvent>,
+    save_settings: Res<SceneSaveSettings>,
+) {
+    if state.recoveries.is_empty() {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let spacing = UI_CONFIG.spacing;
+    let mut resolved: Vec<(SceneRecovery, bool)> = Vec::new();
+    Window::new("Recover Autosave")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .frame(make_frame_solid_via_context(
+            egui::Frame::window(&ctx.style()),
+            ctx,
+        ))
+        .show(ctx, |ui| {
+            ui.label("The editor closed with changes that were not saved:");
+            ui.add_space(spacing);
+            for recovery in state.recoveries.iter() {
+                ui.horizontal(|ui| {
+                    let saved = chrono::DateTime::<chrono::Local>::from(recovery.modified);
+                    ui.label(format!(
+                        "{} (autosaved {})",
+                        recovery.source,
+                        saved.format("%Y-%m-%d %H:%M")
+                    ));
+                    if ui.button("Restore").clicked() {
+                        resolved.push((recovery.clone(), true));
+                    }
+                    if ui.button("Discard").clicked() {
+                        resolved.push((recovery.clone(), false));
+                    }
+                });
+            }
+        });
+
+    for (recovery, restore) in resolved {
+        state
+            .recoveries
+            .retain(|pending| pending.source != recovery.source);
+
+        if restore {
+            let scene_path = rel_asset_to_absolute(&recovery.source).to_string();
+            if let Err(e) = restore_scene_backup(
+                &scene_path,
+                &recovery.autosave,
+                save_settings.backups.max(1),
+            ) {
+                log!(
+                    LogType::Editor,
+                    LogLevel::Error,
+                    LogCategory::System,
+                    "Failed to restore autosave of '{}': {}",
+                    recovery.source,
+                    e
+                );
+                continue;
+            }
+
+            despawn_source_entities(&mut commands, &source_query, &recovery.source);
+            load_writer.write(RequestLoadEvent(recovery.source.clone()));
+            log!(
+                LogType::Editor,
+                LogLevel::OK,
+                LogCategory::System,
+                "Restored autosave of '{}', the previous scene is kept as a backup",
+                recovery.source
+            );
+        }
+        let _ = std::fs::remove_file(&recovery.autosave);
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     editor_state::{
         dock::{load_dock_state, DockLayoutStr},
         editor::save_editor_settings_from_widget_data,
-        EditorState,
+        AutosaveSettings, EditorState,
     },
     interface::{
         layout::{DockState, SidePanelPosition},
@@ -51,6 +51,10 @@ pub struct EditorSettingsTabData {
     pub import_state: ImportState,
     pub dock: DockState,
     pub show_help_on_start: bool,
+    #[serde(default)]
+    pub hot_reload_scenes: bool,
+    #[serde(default)]
+    pub autosave: AutosaveSettings,
 
     #[serde(skip)]
     pub save_requested: bool,
@@ -70,6 +74,8 @@ impl Default for EditorSettingsTabData {
                 changed: true,
             },
             show_help_on_start: true,
+            hot_reload_scenes: false,
+            autosave: AutosaveSettings::default(),
             viewport: ViewportState::default(),
         }
     }
@@ -156,6 +162,14 @@ pub fn update_editor_settings_tab_system(
                 debug_config.line.width = data.viewport.visualizers.debug_line_thickness;
             }
 
+            if data.hot_reload_scenes != editor_state.config.hot_reload_scenes {
+                editor_state.config.hot_reload_scenes = data.hot_reload_scenes;
+            }
+
+            if data.autosave != editor_state.config.autosave {
+                editor_state.config.autosave = data.autosave.clone();
+            }
+
             if data.save_requested {
                 save_editor_settings_from_widget_data(
                     &mut editor_state,
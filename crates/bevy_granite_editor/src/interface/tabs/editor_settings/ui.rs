@@ -1,5 +1,6 @@
 use super::{EditorSettingsTabData, SettingsTab};
 use crate::{
+    editor_state::AutosaveSettings,
     interface::{
         layout::SidePanelPosition, tabs::editor_settings::ImportState, themes::ThemeState,
     },
@@ -519,6 +520,57 @@ fn build_grid_section(ui: &mut egui::Ui, viewport: &mut ViewportState) {
         });
     });
 }
+fn build_scene_section(ui: &mut egui::Ui, hot_reload_scenes: &mut bool) {
+    let large_spacing = crate::UI_CONFIG.large_spacing;
+    ui.vertical(|ui| {
+        ui.group(|ui| {
+            ui.add_space(large_spacing);
+            labeled_checkbox_columns(
+                ui,
+                "Hot Reload Scenes:",
+                hot_reload_scenes,
+                Some("Reload loaded scenes when their file is changed outside the editor. Scenes with unsaved changes are never reloaded"),
+            );
+        });
+    });
+}
+
+fn build_autosave_section(ui: &mut egui::Ui, autosave: &mut AutosaveSettings) {
+    let spacing = crate::UI_CONFIG.spacing;
+    let large_spacing = crate::UI_CONFIG.large_spacing;
+    ui.vertical(|ui| {
+        ui.group(|ui| {
+            ui.add_space(large_spacing);
+            labeled_checkbox_columns(
+                ui,
+                "Autosave:",
+                &mut autosave.enabled,
+                Some("Save every loaded scene to the recovery folder on a timer. The scene files themselves are not touched"),
+            );
+
+            ui.add_space(spacing);
+            labeled_slider_columns(
+                ui,
+                "Autosave Interval:",
+                &mut autosave.interval_secs,
+                30..=1800,
+                30,
+                0,
+                Some(" s"),
+                Some("Seconds between autosaves"),
+            );
+
+            ui.add_space(spacing);
+            ui.columns(2, |columns| {
+                columns[0]
+                    .label("Recovery Folder:")
+                    .on_hover_text("Where autosaves are written, relative to /assets");
+                columns[1].text_edit_singleline(&mut autosave.recovery_dir);
+            });
+        });
+    });
+}
+
 // only obj right now, so a single section
 fn build_import_settings_section(ui: &mut egui::Ui, data: &mut ImportState) {
     let large_spacing = crate::UI_CONFIG.large_spacing;
@@ -570,6 +622,8 @@ fn build_interface_tab(ui: &mut egui::Ui, data: &mut EditorSettingsTabData) {
         .show(ui, |ui| {
             build_theme_section(ui, &mut data.theme_state);
             build_dock_section(ui, &mut data.dock);
+            build_scene_section(ui, &mut data.hot_reload_scenes);
+            build_autosave_section(ui, &mut data.autosave);
         });
 }
 
@@ -4,7 +4,9 @@ use super::widgets::{
     EntityGlobalTransformData, EntityIdentityData, EntityRegisteredData, MaterialTab,
 };
 use bevy::prelude::Entity;
-use bevy_granite_core::{AvailableEditableMaterials, ComponentEditor, NewEditableMaterial};
+use bevy_granite_core::{
+    AvailableEditableMaterials, ComponentEditor, NewEditableMaterial, PreservedSceneData,
+};
 
 #[derive(PartialEq, Clone)]
 pub struct EntityEditorTabData {
@@ -14,6 +16,7 @@ pub struct EntityEditorTabData {
     pub identity_data: EntityIdentityData,
     pub global_transform_data: EntityGlobalTransformData,
     pub registered_data: EntityRegisteredData,
+    pub preserved_data: Option<PreservedSceneData>,
     pub component_editor: Option<ComponentEditor>,
     pub registered_type_names: Vec<Cow<'static, str>>, // Parity with the PostStartup bevy resource
     pub material_builder_open: bool,
@@ -38,6 +41,7 @@ impl Default for EntityEditorTabData {
             identity_data: Default::default(),
             global_transform_data: Default::default(),
             registered_data: Default::default(),
+            preserved_data: None,
             registered_type_names: Vec::new(),
             material_builder_open: false,
             material_to_build: Default::default(),
@@ -113,6 +113,11 @@ pub fn update_entity_editor_tab_system(
                 data.available_materials = available_materials.as_ref().clone();
             }
 
+            // Read-only, so it simply follows the cache
+            if data.preserved_data != cache.data.preserved {
+                data.preserved_data = cache.data.preserved.clone();
+            }
+
             update_identity_from_cache(identity_data, &mut cache, &mut identity_updated_writer);
             update_transform_from_cache(
                 global_transform_data,
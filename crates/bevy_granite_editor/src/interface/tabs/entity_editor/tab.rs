@@ -4,7 +4,7 @@ use super::{
     data::EntityEditorTabData,
     widgets::{
         entity_component_widget, entity_identity_widget,
-        entity_name_widget, entity_transform_widget,
+        entity_name_widget, entity_preserved_data_widget, entity_transform_widget,
     },
 };
 
@@ -13,4 +13,5 @@ pub fn entity_editor_tab_ui(ui: &mut egui::Ui, data: &mut EntityEditorTabData) {
     entity_transform_widget(ui, data);
     entity_identity_widget(ui, data);
     entity_component_widget(ui, data);
+    entity_preserved_data_widget(ui, data);
 }
@@ -2,8 +2,8 @@ use crate::interface::shared::material_selector_combo;
 use bevy::pbr::StandardMaterial;
 use bevy_egui::egui;
 use bevy_granite_core::{
-    AvailableEditableMaterials, EditableMaterial, EditableMaterialField, NewEditableMaterial,
-    StandardMaterialDef,
+    absolute_asset_to_rel, shared::rel_asset_to_absolute, AvailableEditableMaterials,
+    EditableMaterial, EditableMaterialField, NewEditableMaterial, StandardMaterialDef,
 };
 use bevy_granite_logging::{
     config::{LogCategory, LogLevel, LogType},
@@ -269,19 +269,16 @@ pub fn display_material_creation(ui: &mut egui::Ui, new: &mut NewEditableMateria
 
                     ui.spacing_mut().button_padding = egui::Vec2::new(2.0, 2.0);
                     if ui.button("📁").clicked() {
-                        let current_dir = std::env::current_dir().unwrap();
-                        let assets_dir = current_dir.join("assets");
-                        let base_dir = assets_dir.join("materials");
+                        let base_dir = rel_asset_to_absolute("materials").to_string();
 
                         if let Some(folder) = FileDialog::new()
                             .set_location(&base_dir)
                             .show_open_single_dir()
                             .unwrap()
                         {
-                            let relative_path = folder
-                                .strip_prefix(&assets_dir)
-                                .map(|p| p.to_string_lossy().replace("\\", "/"))
-                                .unwrap_or_else(|_| folder.to_string_lossy().into());
+                            let relative_path =
+                                absolute_asset_to_rel(folder.to_string_lossy().to_string())
+                                    .to_string();
 
                             new.file_dir = relative_path;
                             changed = true;
@@ -636,21 +633,15 @@ fn display_text_field(
 
                 ui.spacing_mut().button_padding = egui::Vec2::new(2.0, 2.0);
                 if ui.button("📁").clicked() {
-                    let current_dir = std::env::current_dir().unwrap();
-                    let assets_dir = current_dir.join("assets");
-                    let tex_path = assets_dir.join("textures");
+                    let tex_path = rel_asset_to_absolute("textures").to_string();
                     if let Some(path) = FileDialog::new()
                         .add_filter("Texture Files", &["png", "jpg", "jpeg"])
                         .set_location(&tex_path)
                         .show_open_single_file()
                         .unwrap()
                     {
-                        let relative_path = if let Ok(rel_path) = path.strip_prefix(&assets_dir) {
-                            rel_path.to_string_lossy().to_string().replace("\\", "/")
-                        } else {
-                            path.to_string_lossy().to_string()
-                        };
-                        *val = relative_path;
+                        *val =
+                            absolute_asset_to_rel(path.to_string_lossy().to_string()).to_string();
 
                         changed = true;
                     }
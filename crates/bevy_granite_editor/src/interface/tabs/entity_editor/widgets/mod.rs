@@ -2,10 +2,12 @@ pub mod component_editor;
 pub mod identity_editor;
 pub mod material_editor;
 pub mod name_editor;
+pub mod preserved_data;
 pub mod transform_editor;
 
 pub use component_editor::*;
 pub use identity_editor::*;
 pub use material_editor::*;
 pub use name_editor::*;
+pub use preserved_data::*;
 pub use transform_editor::*;
\ No newline at end of file
@@ -0,0 +1,49 @@
+use crate::interface::tabs::EntityEditorTabData;
+use bevy_egui::egui;
+
+// Scene data this app could not load (unknown class or unregistered components)
+// Shown read-only, it is written back to the scene exactly as it was loaded
+pub fn entity_preserved_data_widget(ui: &mut egui::Ui, data: &EntityEditorTabData) {
+    let Some(preserved) = data.preserved_data.as_ref() else {
+        return;
+    };
+    let large_spacing = crate::UI_CONFIG.large_spacing;
+
+    ui.group(|ui| {
+        ui.set_min_width(ui.available_width());
+        ui.horizontal(|ui| {
+            ui.add_space(large_spacing);
+            ui.vertical(|ui| {
+                ui.add_space(large_spacing);
+                ui.label(egui::RichText::new("Preserved Scene Data").strong());
+                ui.label(
+                    egui::RichText::new("Not known to this app, saved back unchanged")
+                        .italics()
+                        .weak(),
+                );
+
+                if let Some(class) = preserved.class.as_ref() {
+                    display_raw_ron(ui, "Class", class);
+                }
+                for (type_name, ron) in preserved.components.iter() {
+                    display_raw_ron(ui, type_name, ron);
+                }
+                ui.add_space(large_spacing);
+            });
+            ui.add_space(large_spacing);
+        });
+    });
+}
+
+fn display_raw_ron(ui: &mut egui::Ui, label: &str, ron: &str) {
+    egui::CollapsingHeader::new(label)
+        .id_salt(("preserved_scene_data", label))
+        .show(ui, |ui| {
+            let mut ron = ron;
+            ui.add(
+                egui::TextEdit::multiline(&mut ron)
+                    .code_editor()
+                    .desired_width(f32::INFINITY),
+            );
+        });
+}
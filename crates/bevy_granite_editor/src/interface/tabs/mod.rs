@@ -3,6 +3,7 @@ pub mod editor_settings;
 pub mod entity_editor;
 pub mod log;
 pub mod node_tree;
+pub mod scene_properties;
 
 pub use debug::{debug_tab_ui, update_debug_tab_ui_system, DebugTabData};
 pub use editor_settings::{update_editor_settings_tab_system, EditorSettingsTabData, SettingsTab};
@@ -13,3 +14,6 @@ pub use entity_editor::{
 };
 pub use log::{log_tab_ui, update_log_tab_system, LogTabData};
 pub use node_tree::{update_node_tree_tabs_system, NodeTreeTabData, RequestReparentEntityEvent};
+pub use scene_properties::{
+    scene_properties_tab_ui, update_scene_properties_tab_system, ScenePropertiesTabData,
+};
@@ -0,0 +1,40 @@
+use crate::{
+    editor_state::EditorState,
+    interface::{SideDockState, SideTab},
+};
+use bevy::prelude::ResMut;
+use bevy_granite_core::SceneMetadataStore;
+
+/// Keeps the Scene Properties tab in sync with the metadata of the active scene
+/// Edits are written straight into the SceneMetadataStore and the scene is marked unsaved
+pub fn update_scene_properties_tab_system(
+    mut side_dock: ResMut<SideDockState>,
+    mut editor_state: ResMut<EditorState>,
+    mut metadata_store: ResMut<SceneMetadataStore>,
+) {
+    for (_, tab) in side_dock.dock_state.iter_all_tabs_mut() {
+        if let SideTab::SceneProperties { ref mut data } = tab {
+            let source = editor_state.current_file.clone();
+
+            if data.changed {
+                data.changed = false;
+                if let Some(source) = &source {
+                    let metadata = metadata_store.get_or_default(source);
+                    data.apply_to(metadata);
+                    data.metadata = metadata.clone();
+                    editor_state.unsaved_sources.insert(source.clone());
+                }
+            }
+
+            // Reloads, saves and switching scenes all show up as different metadata
+            let stored = source
+                .as_deref()
+                .and_then(|source| metadata_store.get(source))
+                .cloned()
+                .unwrap_or_default();
+            if data.source != source || data.metadata != stored {
+                data.load(source, stored);
+            }
+        }
+    }
+}
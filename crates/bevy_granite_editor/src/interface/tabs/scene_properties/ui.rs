@@ -0,0 +1,180 @@
+use bevy_egui::egui;
+use bevy_granite_core::SceneMetadata;
+
+#[derive(PartialEq, Clone, Default)]
+pub struct CustomFieldEdit {
+    pub key: String,
+    pub value: String,
+    pub valid: bool,
+}
+
+// Text copies of the metadata, so half typed tags and RON values do not fight the store
+#[derive(PartialEq, Clone, Default)]
+pub struct ScenePropertiesTabData {
+    pub source: Option<String>,
+    pub metadata: SceneMetadata,
+    pub description: String,
+    pub author: String,
+    pub tags: String,
+    pub custom: Vec<CustomFieldEdit>,
+    pub new_key: String,
+    pub changed: bool,
+}
+
+impl ScenePropertiesTabData {
+    pub fn load(&mut self, source: Option<String>, metadata: SceneMetadata) {
+        self.description = metadata.description.clone().unwrap_or_default();
+        self.author = metadata.author.clone().unwrap_or_default();
+        self.tags = metadata.tags.join(", ");
+        self.custom = metadata
+            .custom
+            .iter()
+            .map(|(key, value)| CustomFieldEdit {
+                key: key.clone(),
+                value: ron::to_string(value).unwrap_or_default(),
+                valid: true,
+            })
+            .collect();
+        self.source = source;
+        self.metadata = metadata;
+    }
+
+    /// Write the edited fields into metadata
+    /// Custom values that are not valid RON keep whatever they were before
+    pub fn apply_to(&mut self, metadata: &mut SceneMetadata) {
+        let non_empty = |text: &str| Some(text.trim().to_string()).filter(|text| !text.is_empty());
+        metadata.description = non_empty(&self.description);
+        metadata.author = non_empty(&self.author);
+        metadata.tags = self.tags.split(',').filter_map(non_empty).collect();
+
+        let previous = std::mem::take(&mut metadata.custom);
+        for field in self.custom.iter_mut() {
+            let key = field.key.trim();
+            if key.is_empty() {
+                continue;
+            }
+            match ron::from_str::<ron::Value>(&field.value) {
+                Ok(value) => {
+                    field.valid = true;
+                    metadata.custom.insert(key.to_string(), value);
+                }
+                Err(_) => {
+                    field.valid = false;
+                    if let Some(value) = previous.get(key) {
+                        metadata.custom.insert(key.to_string(), value.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn scene_properties_tab_ui(ui: &mut egui::Ui, data: &mut ScenePropertiesTabData) {
+    let spacing = crate::UI_CONFIG.spacing;
+    let large_spacing = crate::UI_CONFIG.large_spacing;
+
+    let Some(source) = data.source.clone() else {
+        ui.label("No active scene");
+        return;
+    };
+
+    ui.label(egui::RichText::new(source).italics());
+    ui.add_space(large_spacing);
+
+    let mut changed = false;
+    egui::Grid::new("scene_properties_grid")
+        .num_columns(2)
+        .spacing([large_spacing, large_spacing])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Description:");
+            changed |= ui
+                .add(egui::TextEdit::multiline(&mut data.description).desired_rows(2))
+                .changed();
+            ui.end_row();
+
+            ui.label("Author:");
+            changed |= ui.text_edit_singleline(&mut data.author).changed();
+            ui.end_row();
+
+            ui.label("Tags:");
+            changed |= ui
+                .add(egui::TextEdit::singleline(&mut data.tags).hint_text("comma separated"))
+                .changed();
+            ui.end_row();
+
+            ui.label("Created:");
+            ui.weak(data.metadata.created.as_deref().unwrap_or("-"));
+            ui.end_row();
+
+            ui.label("Modified:");
+            ui.weak(data.metadata.modified.as_deref().unwrap_or("-"));
+            ui.end_row();
+
+            ui.label("Version:");
+            ui.weak(&data.metadata.format_version);
+            ui.end_row();
+        });
+
+    ui.add_space(large_spacing);
+    ui.collapsing("Custom", |ui| {
+        ui.weak("Values are RON, e.g. 9.81, \"Level 1\" or [1, 2]");
+        ui.add_space(spacing);
+
+        let mut remove = None;
+        egui::Grid::new("scene_properties_custom_grid")
+            .num_columns(3)
+            .spacing([spacing, spacing])
+            .striped(true)
+            .show(ui, |ui| {
+                for (index, field) in data.custom.iter_mut().enumerate() {
+                    changed |= ui
+                        .add(egui::TextEdit::singleline(&mut field.key).desired_width(100.0))
+                        .changed();
+
+                    let mut value_edit = egui::TextEdit::singleline(&mut field.value);
+                    if !field.valid {
+                        value_edit = value_edit.text_color(ui.visuals().error_fg_color);
+                    }
+                    changed |= ui.add(value_edit).changed();
+
+                    if ui.small_button("Delete").clicked() {
+                        remove = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(index) = remove {
+            data.custom.remove(index);
+            changed = true;
+        }
+
+        ui.add_space(spacing);
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut data.new_key)
+                    .hint_text("key")
+                    .desired_width(100.0),
+            );
+            let key = data.new_key.trim().to_string();
+            let exists = data.custom.iter().any(|field| field.key.trim() == key);
+            if ui
+                .add_enabled(!key.is_empty() && !exists, egui::Button::new("Add"))
+                .clicked()
+            {
+                data.custom.push(CustomFieldEdit {
+                    key,
+                    value: "()".to_string(),
+                    valid: true,
+                });
+                data.new_key.clear();
+                changed = true;
+            }
+        });
+    });
+
+    if changed {
+        data.changed = true;
+    }
+}
@@ -22,7 +22,7 @@ pub use editor_state::{
 pub use entities::get_entity_bounds_or_fallback;
 pub use interface::events::{
     RequestCameraEntityFrame, RequestEditorToggle, RequestNewParent, RequestRemoveChildren,
-    RequestRemoveParents, RequestToggleCameraSync,
+    RequestRemoveParents, RequestSaveSelection, RequestToggleCameraSync,
 };
 
 pub struct BevyGraniteEditor {
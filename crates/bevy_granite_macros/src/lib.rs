@@ -2,9 +2,20 @@ use once_cell::sync::Lazy;
 use proc_macro::TokenStream;
 use quote::quote;
 use std::sync::Mutex;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{
+    parse::Parser, parse_macro_input, punctuated::Punctuated, DeriveInput, Expr, ExprLit, Lit,
+    Token,
+};
 
-static REGISTERED_COMPONENTS: Lazy<Mutex<Vec<(String, bool)>>> =
+// Seen by #[granite_component], registered by register_editor_components!
+struct RegisteredComponent {
+    name: String,
+    ui_hidden: bool,
+    versioned: bool,
+    hooks: bool,
+}
+
+static REGISTERED_COMPONENTS: Lazy<Mutex<Vec<RegisteredComponent>>> =
     Lazy::new(|| Mutex::new(Vec::new()));
 
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -17,13 +28,62 @@ pub fn granite_component(attr: TokenStream, item: TokenStream) -> TokenStream {
     let name = &input.ident;
     let name_str = name.to_string();
     println!("MACRO: Registering component: {}", name_str);
-    let attr_str = attr.to_string();
-    let include_default = attr_str.contains("default");
-    let is_hidden = attr_str.contains("ui_hidden");
+
+    // default and ui_hidden flags, version = N,
+    // migrate = fn(from_version, ron::Value) -> Result<ron::Value, String>
+    // hooks registers the component's GraniteComponentHooks impl
+    let mut include_default = false;
+    let mut is_hidden = false;
+    let mut version = None;
+    let mut migrate = None;
+    let mut has_hooks = false;
+    let args = match Punctuated::<Expr, Token![,]>::parse_terminated.parse(attr) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    for arg in args {
+        // Flags are written bare or as strings, e.g. ui_hidden or "default"
+        let flag = match &arg {
+            Expr::Path(flag) => flag.path.get_ident().map(|ident| ident.to_string()),
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(flag),
+                ..
+            }) => Some(flag.value()),
+            _ => None,
+        };
+        match flag.as_deref() {
+            Some("default") => include_default = true,
+            Some("ui_hidden") => is_hidden = true,
+            Some("hooks") => has_hooks = true,
+            _ => {}
+        }
+        let Expr::Assign(assign) = arg else {
+            continue;
+        };
+        let Expr::Path(key) = assign.left.as_ref() else {
+            continue;
+        };
+        if key.path.is_ident("version") {
+            version = Some(assign.right);
+        } else if key.path.is_ident("migrate") {
+            migrate = Some(assign.right);
+        }
+    }
+    if let (None, Some(migrate)) = (&version, &migrate) {
+        return syn::Error::new_spanned(migrate, "migrate needs a version, e.g. version = 2")
+            .to_compile_error()
+            .into();
+    }
+
     REGISTERED_COMPONENTS
         .lock()
         .unwrap()
-        .push((name_str.clone(), is_hidden));
+        .push(RegisteredComponent {
+            name: name_str.clone(),
+            ui_hidden: is_hidden,
+            versioned: version.is_some(),
+            hooks: has_hooks,
+        });
     let derives = if include_default {
         quote! {
             #[derive(Reflect, Serialize, Deserialize, Debug, Clone, Component, PartialEq)]
@@ -47,11 +107,33 @@ pub fn granite_component(attr: TokenStream, item: TokenStream) -> TokenStream {
     } else {
         quote! {}
     };
+    let migration = match version {
+        Some(version) => {
+            let migrate = match migrate {
+                Some(migrate) => quote! { (#migrate)(from_version, value) },
+                None => quote! { Ok(value) },
+            };
+            quote! {
+                impl bevy_granite::prelude::GraniteComponentMigration for #name {
+                    const VERSION: u32 = #version;
+
+                    fn migrate(
+                        from_version: u32,
+                        value: bevy_granite::bevy_granite_core::ron::Value,
+                    ) -> Result<bevy_granite::bevy_granite_core::ron::Value, String> {
+                        #migrate
+                    }
+                }
+            }
+        }
+        None => quote! {},
+    };
     let expanded = quote! {
         #imports
         #derives
         #[reflect(Component, Serialize, Deserialize, Default, FromReflect)]
         #input
+        #migration
     };
     TokenStream::from(expanded)
 }
@@ -66,17 +148,35 @@ pub fn register_editor_components(input: TokenStream) -> TokenStream {
     };
 
     let components = REGISTERED_COMPONENTS.lock().unwrap();
-    let tokens = components.iter().map(|(name, is_hidden)| {
-        let ident = syn::Ident::new(name, proc_macro2::Span::call_site());
+    let tokens = components.iter().map(|component| {
+        let ident = syn::Ident::new(&component.name, proc_macro2::Span::call_site());
+        let migrator = if component.versioned {
+            quote! {
+                #app_name.register_type_data::<#ident, bevy_granite::prelude::ComponentMigrator>();
+            }
+        } else {
+            quote! {}
+        };
+        let hooks = if component.hooks {
+            quote! {
+                #app_name.register_type_data::<#ident, bevy_granite::prelude::ReflectGraniteComponentHooks>();
+            }
+        } else {
+            quote! {}
+        };
 
-        if *is_hidden {
+        if component.ui_hidden {
             quote! {
                 #app_name.register_type::<#ident>();
+                #migrator
+                #hooks
             }
         } else {
             quote! {
                 #app_name.register_type::<#ident>();
                 #app_name.register_type_data::<#ident, bevy_granite::prelude::BridgeTag>();
+                #migrator
+                #hooks
             }
         }
     });
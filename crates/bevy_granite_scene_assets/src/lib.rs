@@ -0,0 +1,51 @@
+use bevy::{
+    pbr::{MeshMaterial3d, StandardMaterial},
+    prelude::*,
+    render::mesh::Mesh3d,
+};
+
+// lib.rs
+// Granite exports sources as Bevy DynamicScenes (.scn.ron). Bevy can not serialize asset handles,
+// so meshes and materials are written as asset paths instead and this crate loads them back.
+// It only depends on Bevy, so games that ship exported scenes do not need Granite itself.
+
+/// Mesh and material of an exported entity by asset path, in place of their handles
+/// Meshes and materials built in code are exported to a .gltf next to the scene and referenced by
+/// label (e.g. `levels/arena.scn.gltf#Mesh0/Primitive0`), so loading those needs Bevy's glTF loader
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq)]
+#[reflect(Component, Default)]
+pub struct DynamicSceneAssetPaths {
+    pub mesh: Option<String>,
+    pub material: Option<String>,
+}
+
+/// Registers DynamicSceneAssetPaths and inserts Mesh3d and MeshMaterial3d when it is added
+pub struct SceneAssetsPlugin;
+impl Plugin for SceneAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DynamicSceneAssetPaths>()
+            .add_observer(load_dynamic_scene_asset_paths_observer);
+    }
+}
+
+/// Load the handles a DynamicSceneAssetPaths points at onto its entity
+pub fn load_dynamic_scene_asset_paths_observer(
+    trigger: Trigger<OnAdd, DynamicSceneAssetPaths>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    paths_query: Query<&DynamicSceneAssetPaths>,
+) {
+    let entity = trigger.target();
+    let Ok(paths) = paths_query.get(entity) else {
+        return;
+    };
+
+    if let Some(mesh) = &paths.mesh {
+        let handle: Handle<Mesh> = asset_server.load(mesh);
+        commands.entity(entity).insert(Mesh3d(handle));
+    }
+    if let Some(material) = &paths.material {
+        let handle: Handle<StandardMaterial> = asset_server.load(material);
+        commands.entity(entity).insert(MeshMaterial3d(handle));
+    }
+}
@@ -139,11 +139,19 @@ pub mod prelude {
     pub use crate::{
         bevy_granite_core,
         bevy_granite_core::{
-            BridgeTag, MainCamera, RequestDespawnBySource, RequestDespawnSerializableEntities,
-            RequestLoadEvent, RequestReloadEvent, RequestSaveEvent, TreeHiddenEntity, UICamera,
-            WorldLoadSuccessEvent, WorldSaveSuccessEvent,
+            serialize_to_string, BridgeTag, ComponentMigrator, GraniteComponentHooks,
+            GraniteComponentMigration, GraniteEntityRef, GranitePaths, GraniteSceneAsset,
+            GraniteSceneError, GraniteUuidIndex, InMemoryScene, MainCamera, PreservedSceneData,
+            ReflectGraniteComponentHooks, RequestDespawnBySource,
+            RequestDespawnSerializableEntities, RequestExportDynamicSceneEvent,
+            RequestExportGltfEvent, RequestImportDynamicSceneEvent, RequestLoadEvent,
+            RequestLoadFromMemoryEvent, RequestLoadWithOptionsEvent, RequestReloadEvent,
+            RequestRestoreBackupEvent, RequestSaveCopyEvent, RequestSaveEntitiesEvent,
+            RequestSaveEvent, RequestSpawnSceneAssetEvent, SceneData, SceneLoadOptions,
+            SceneMetadata, SceneMetadataStore, TreeHiddenEntity, UICamera, WorldLoadFailedEvent,
+            WorldLoadSuccessEvent, WorldSaveFailedEvent, WorldSaveSuccessEvent, WorldSpawnedEvent,
         },
-        bevy_granite_logging::{LogCategory, LogLevel, LogType, log},
+        bevy_granite_logging::{log, LogCategory, LogLevel, LogType},
         bevy_granite_macros::{granite_component, register_editor_components},
     };
 
@@ -155,6 +163,6 @@ pub mod prelude {
     #[cfg(feature = "editor")]
     pub use crate::bevy_granite_editor::{
         RequestCameraEntityFrame, RequestEditorToggle, RequestNewParent, RequestRemoveChildren,
-        RequestRemoveParents, RequestToggleCameraSync,
+        RequestRemoveParents, RequestSaveSelection, RequestToggleCameraSync,
     };
 }